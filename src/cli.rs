@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(name = "eflowcodeline")]
@@ -8,7 +8,7 @@ pub struct Cli {
     #[arg(short = 'c', long = "config")]
     pub config: bool,
 
-    /// Print current configuration
+    /// Print the effective configuration for the current directory
     #[arg(long = "print")]
     pub print: bool,
 
@@ -16,10 +16,26 @@ pub struct Cli {
     #[arg(long = "init")]
     pub init: bool,
 
-    /// Check configuration
+    /// Check configuration and report overridden / ignored keys
     #[arg(long = "check")]
     pub check: bool,
 
+    /// Import a CCometixLine config.toml or ccstatusline JSON config as a theme
+    #[arg(long = "import-theme", value_name = "PATH")]
+    pub import_theme: Option<std::path::PathBuf>,
+
+    /// Output format of the statusline
+    #[arg(long = "format", value_enum, default_value_t = OutputFormat::Ansi)]
+    pub format: OutputFormat,
+
+    /// Render from the last session Claude Code sent instead of reading stdin
+    #[arg(long = "last-session")]
+    pub last_session: bool,
+
+    /// Render the statusline without any colors or escape sequences
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+
     /// Check for updates
     #[arg(short = 'u', long = "update")]
     pub update: bool,
@@ -29,6 +45,20 @@ pub struct Cli {
     pub patch: Option<String>,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// Colored statusline for Claude Code
+    Ansi,
+    /// Collected segment data as JSON
+    Json,
+    /// tmux status line markup
+    Tmux,
+    /// zsh prompt (requires `setopt prompt_subst`)
+    Zsh,
+    /// bash prompt, to be assigned to PS1 from PROMPT_COMMAND
+    Bash,
+}
+
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
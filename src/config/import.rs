@@ -0,0 +1,654 @@
+// Theme import from other statusline tools.
+//
+// Two formats are understood:
+// - CCometixLine `config.toml` (the project EFlowCodeLine was forked from), which
+//   shares our `Config`/`SegmentConfig` layout but predates several segments.
+// - ccstatusline JSON settings, which describe "widgets" on up to three lines
+//   with chalk-style color names.
+//
+// The result is a complete `Config` built on top of the default theme, plus a
+// list of everything that could not be translated.
+
+use super::types::{
+    Align, AnsiColor, ColorConfig, Config, IconConfig, SegmentConfig, SegmentId, StyleMode,
+    TextStyleConfig, Underline,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    CCometixLine,
+    CcStatusline,
+}
+
+impl ImportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportFormat::CCometixLine => "CCometixLine",
+            ImportFormat::CcStatusline => "ccstatusline",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportResult {
+    pub format: ImportFormat,
+    /// Suggested theme name derived from the source file
+    pub theme_name: String,
+    pub config: Config,
+    /// Human-readable notes about settings that were dropped or approximated
+    pub warnings: Vec<String>,
+}
+
+/// Detect the format of `path` and convert it into a `Config`.
+pub fn import_theme(path: &Path) -> Result<ImportResult, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    let is_json = path.extension().and_then(|e| e.to_str()) == Some("json")
+        || content.trim_start().starts_with('{');
+
+    if is_json {
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(from_ccstatusline(&value))
+    } else {
+        let table: toml::Table = toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(from_ccometixline(&table))
+    }
+}
+
+/// Convert a CCometixLine `config.toml`.
+pub fn from_ccometixline(table: &toml::Table) -> ImportResult {
+    let mut warnings = Vec::new();
+    let mut config = crate::ui::themes::ThemePresets::get_default();
+
+    let source_theme = table
+        .get("theme")
+        .and_then(|v| v.as_str())
+        .unwrap_or("imported");
+
+    for (key, value) in table {
+        match key.as_str() {
+            "theme" | "segments" => {}
+            "style" => {
+                let Some(style) = value.as_table() else {
+                    warnings.push("style: expected a table".to_string());
+                    continue;
+                };
+                for (k, v) in style {
+                    match (k.as_str(), v.as_str()) {
+                        ("mode", Some(mode)) => match parse_style_mode(mode) {
+                            Some(mode) => config.style.mode = mode,
+                            None => warnings.push(format!("style.mode: unknown mode \"{}\"", mode)),
+                        },
+                        ("separator", Some(separator)) => {
+                            config.style.separator = separator.to_string()
+                        }
+                        _ => warnings.push(format!("style.{}: not supported", k)),
+                    }
+                }
+            }
+            _ => warnings.push(format!("{}: not supported", key)),
+        }
+    }
+
+    let mut imported = Vec::new();
+    let entries = table.get("segments").and_then(|v| v.as_array());
+    for (index, entry) in entries.into_iter().flatten().enumerate() {
+        let Some(entry) = entry.as_table() else {
+            warnings.push(format!("segments[{}]: expected a table", index));
+            continue;
+        };
+        let Some(raw_id) = entry.get("id").and_then(|v| v.as_str()) else {
+            warnings.push(format!("segments[{}]: missing id", index));
+            continue;
+        };
+        let Some(id) = parse_segment_id(raw_id) else {
+            warnings.push(format!(
+                "segment \"{}\": no EFlowCodeLine equivalent",
+                raw_id
+            ));
+            continue;
+        };
+
+        let mut segment = template_segment(&config, id);
+        for (k, v) in entry {
+            let path = format!("segments.{}.{}", raw_id, k);
+            match k.as_str() {
+                "id" => {}
+                "enabled" => match v.as_bool() {
+                    Some(enabled) => segment.enabled = enabled,
+                    None => warnings.push(format!("{}: expected a boolean", path)),
+                },
+                "icon" => {
+                    for (ik, iv) in v.as_table().into_iter().flatten() {
+                        match (ik.as_str(), iv.as_str()) {
+                            ("plain", Some(icon)) => segment.icon.plain = icon.to_string(),
+                            ("nerd_font", Some(icon)) => segment.icon.nerd_font = icon.to_string(),
+                            _ => warnings.push(format!("{}.{}: not supported", path, ik)),
+                        }
+                    }
+                }
+                "colors" => {
+                    for (ck, cv) in v.as_table().into_iter().flatten() {
+                        let color = parse_toml_color(cv);
+                        if color.is_none() {
+                            warnings.push(format!("{}.{}: unrecognized color {}", path, ck, cv));
+                            continue;
+                        }
+                        match ck.as_str() {
+                            "icon" => segment.colors.icon = color,
+                            "text" => segment.colors.text = color,
+                            "background" => segment.colors.background = color,
+                            _ => warnings.push(format!("{}.{}: not supported", path, ck)),
+                        }
+                    }
+                }
+                "styles" => {
+                    for (sk, sv) in v.as_table().into_iter().flatten() {
+                        if sk == "text_underline" {
+                            match parse_underline(sv) {
+                                Some(underline) => segment.styles.text_underline = underline,
+                                None => warnings.push(format!(
+                                    "{}.{}: unrecognized underline {}",
+                                    path, sk, sv
+                                )),
+                            }
+                            continue;
+                        }
+                        if sk == "underline_color" {
+                            match parse_toml_color(sv) {
+                                Some(color) => segment.styles.underline_color = Some(color),
+                                None => warnings
+                                    .push(format!("{}.{}: unrecognized color {}", path, sk, sv)),
+                            }
+                            continue;
+                        }
+                        match (sk.as_str(), sv.as_bool()) {
+                            ("text_bold", Some(bold)) => segment.styles.text_bold = bold,
+                            ("text_italic", Some(italic)) => segment.styles.text_italic = italic,
+                            ("text_dim", Some(dim)) => segment.styles.text_dim = dim,
+                            ("text_strikethrough", Some(strike)) => {
+                                segment.styles.text_strikethrough = strike
+                            }
+                            ("text_inverse", Some(inverse)) => {
+                                segment.styles.text_inverse = inverse
+                            }
+                            _ => warnings.push(format!("{}.{}: not supported", path, sk)),
+                        }
+                    }
+                }
+                "options" => {
+                    for (ok, ov) in v.as_table().into_iter().flatten() {
+                        match serde_json::to_value(ov) {
+                            Ok(json) => {
+                                segment.options.insert(ok.clone(), json);
+                            }
+                            Err(_) => warnings.push(format!("{}.{}: not supported", path, ok)),
+                        }
+                    }
+                }
+                _ => warnings.push(format!("{}: not supported", path)),
+            }
+        }
+        push_unique(&mut imported, segment, &mut warnings);
+    }
+
+    let theme_name = format!("cometix-{}", theme_slug(source_theme));
+    // CCometixLine renders a single line
+    finish(
+        config,
+        imported,
+        Vec::new(),
+        ImportFormat::CCometixLine,
+        theme_name,
+        warnings,
+    )
+}
+
+/// Convert a ccstatusline JSON settings file.
+pub fn from_ccstatusline(value: &serde_json::Value) -> ImportResult {
+    let mut warnings = Vec::new();
+    let mut config = crate::ui::themes::ThemePresets::get_default();
+
+    let powerline = value
+        .get("powerline")
+        .and_then(|p| p.get("enabled"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if powerline {
+        config.style.mode = StyleMode::NerdFont;
+        config.style.separator = value
+            .pointer("/powerline/separators/0")
+            .and_then(|v| v.as_str())
+            .unwrap_or("\u{e0b0}")
+            .to_string();
+        let cap = |pointer: &str| {
+            value
+                .pointer(pointer)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        config.style.start_cap = cap("/powerline/startCaps/0");
+        config.style.end_cap = cap("/powerline/endCaps/0");
+    } else {
+        config.style.mode = StyleMode::Plain;
+        let separator = value
+            .get("defaultSeparator")
+            .and_then(|v| v.as_str())
+            .unwrap_or("|");
+        config.style.separator = format!(" {} ", separator.trim());
+    }
+
+    let global_bold = value
+        .get("globalBold")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let lines = value
+        .get("lines")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    // Each ccstatusline line becomes one row of the layout
+    let mut imported = Vec::new();
+    let mut rows = Vec::new();
+    for line in lines.iter().filter_map(|l| l.as_array()) {
+        let mut row = Vec::new();
+        for widget in line {
+            let kind = widget.get("type").and_then(|v| v.as_str()).unwrap_or("");
+            if matches!(kind, "separator" | "flex-separator") {
+                continue;
+            }
+            let Some(id) = ccstatusline_widget_id(kind) else {
+                warnings.push(format!("widget \"{}\": no EFlowCodeLine equivalent", kind));
+                continue;
+            };
+
+            let mut segment = template_segment(&config, id);
+            segment.enabled = true;
+            if !powerline {
+                segment.colors.background = None;
+            }
+
+            if let Some(color) = widget.get("color").and_then(|v| v.as_str()) {
+                match parse_color_name(color) {
+                    Some(c) => {
+                        segment.colors.icon = Some(c.clone());
+                        segment.colors.text = Some(c);
+                    }
+                    None => warnings.push(format!(
+                        "widget \"{}\": unrecognized color \"{}\"",
+                        kind, color
+                    )),
+                }
+            }
+            if let Some(bg) = widget.get("backgroundColor").and_then(|v| v.as_str()) {
+                match parse_color_name(bg) {
+                    Some(c) => segment.colors.background = Some(c),
+                    None => warnings.push(format!(
+                        "widget \"{}\": unrecognized background \"{}\"",
+                        kind, bg
+                    )),
+                }
+            }
+            segment.styles.text_bold = global_bold
+                || widget
+                    .get("bold")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+            if id == SegmentId::Branding {
+                if let Some(text) = widget.get("customText").and_then(|v| v.as_str()) {
+                    segment.icon = IconConfig {
+                        plain: text.to_string(),
+                        nerd_font: text.to_string(),
+                    };
+                }
+            }
+
+            if push_unique(&mut imported, segment, &mut warnings) {
+                row.push(id);
+            }
+        }
+        if !row.is_empty() {
+            rows.push(row);
+        }
+    }
+
+    warnings
+        .push("ccstatusline has no segment icons; EFlowCodeLine default icons kept".to_string());
+
+    finish(
+        config,
+        imported,
+        rows,
+        ImportFormat::CcStatusline,
+        "ccstatusline".to_string(),
+        warnings,
+    )
+}
+
+/// Imported segments first, in source order; the remaining theme segments are
+/// kept after them, disabled, so they can be switched on in the TUI later.
+/// `rows` replaces the theme's row layout.
+fn finish(
+    mut config: Config,
+    imported: Vec<SegmentConfig>,
+    rows: Vec<Vec<SegmentId>>,
+    format: ImportFormat,
+    theme_name: String,
+    mut warnings: Vec<String>,
+) -> ImportResult {
+    let mut leftover = Vec::new();
+    for segment in &config.segments {
+        if !imported.iter().any(|s| s.id == segment.id) {
+            let mut segment = segment.clone();
+            segment.enabled = false;
+            leftover.push(segment);
+        }
+    }
+    if !leftover.is_empty() {
+        let names: Vec<String> = leftover.iter().map(|s| segment_key(s.id)).collect();
+        warnings.push(format!(
+            "not in the source, added disabled: {}",
+            names.join(", ")
+        ));
+    }
+
+    config.rows = rows;
+    config.segments = imported;
+    config.segments.extend(leftover);
+    config.theme = theme_name.clone();
+
+    ImportResult {
+        format,
+        theme_name,
+        config,
+        warnings,
+    }
+}
+
+/// Add `segment` unless its id was already imported; returns whether it was added.
+fn push_unique(
+    imported: &mut Vec<SegmentConfig>,
+    segment: SegmentConfig,
+    warnings: &mut Vec<String>,
+) -> bool {
+    if imported.iter().any(|s| s.id == segment.id) {
+        warnings.push(format!(
+            "segment \"{}\" appears more than once; kept the first",
+            segment_key(segment.id)
+        ));
+        false
+    } else {
+        imported.push(segment);
+        true
+    }
+}
+
+/// Theme segment for `id`, or a blank one if the theme does not include it.
+fn template_segment(config: &Config, id: SegmentId) -> SegmentConfig {
+    config
+        .segments
+        .iter()
+        .find(|s| s.id == id)
+        .cloned()
+        .unwrap_or_else(|| SegmentConfig {
+            id,
+            enabled: true,
+            icon: IconConfig {
+                plain: String::new(),
+                nerd_font: String::new(),
+            },
+            colors: ColorConfig {
+                icon: None,
+                text: None,
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: HashMap::new(),
+            thresholds: Vec::new(),
+            align: Align::Left,
+            priority: None,
+            min_width: None,
+        })
+}
+
+fn segment_key(id: SegmentId) -> String {
+    serde_json::to_value(id)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", id))
+}
+
+/// `raw` lowercased, with every run of other characters than `[a-z0-9]`
+/// turned into one `-`, so it can name a theme file.
+fn theme_slug(raw: &str) -> String {
+    let mut slug = String::new();
+    for c in raw.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "imported".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn parse_segment_id(raw: &str) -> Option<SegmentId> {
+    serde_json::from_value(serde_json::Value::String(raw.to_string())).ok()
+}
+
+fn parse_style_mode(raw: &str) -> Option<StyleMode> {
+    serde_json::from_value(serde_json::Value::String(raw.to_string())).ok()
+}
+
+/// Map a ccstatusline widget type onto the closest segment.
+fn ccstatusline_widget_id(kind: &str) -> Option<SegmentId> {
+    match kind {
+        "model" => Some(SegmentId::Model),
+        "context-length" | "context-percentage" | "context-percentage-usable" => {
+            Some(SegmentId::ContextWindow)
+        }
+        "git-branch" | "git-changes" => Some(SegmentId::Git),
+        "session-clock" => Some(SegmentId::Session),
+        "session-cost" => Some(SegmentId::Cost),
+        "current-working-dir" => Some(SegmentId::Cwd),
+        "output-style" => Some(SegmentId::OutputStyle),
+        "custom-text" => Some(SegmentId::Branding),
+        _ => None,
+    }
+}
+
+/// An underline shape by name, or `true`/`false` for a plain underline.
+fn parse_underline(value: &toml::Value) -> Option<Underline> {
+    match value {
+        toml::Value::Boolean(true) => Some(Underline::Single),
+        toml::Value::Boolean(false) => Some(Underline::None),
+        _ => value.clone().try_into().ok(),
+    }
+}
+
+/// A CCometixLine color is either our own `{ c16 | c256 | r,g,b }` table or a name.
+fn parse_toml_color(value: &toml::Value) -> Option<AnsiColor> {
+    match value {
+        toml::Value::String(name) => parse_color_name(name),
+        other => other.clone().try_into().ok(),
+    }
+}
+
+/// Parse chalk/ccstatusline style color names: `cyan`, `brightBlue`, `bgRed`,
+/// `gray`, `hex:ff8800`, `#ff8800`, `ansi256:208`.
+pub fn parse_color_name(raw: &str) -> Option<AnsiColor> {
+    let raw = raw.trim();
+
+    if let Some(hex) = raw.strip_prefix("hex:").or_else(|| raw.strip_prefix('#')) {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(AnsiColor::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+    if let Some(index) = raw.strip_prefix("ansi256:") {
+        return index.parse().ok().map(|c256| AnsiColor::Color256 { c256 });
+    }
+
+    let mut name: String = raw
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_lowercase();
+    if let Some(stripped) = name.strip_prefix("bg") {
+        name = stripped.to_string();
+    }
+
+    let (bright, base) = match name.strip_prefix("bright") {
+        Some(base) => (true, base.to_string()),
+        None => (false, name),
+    };
+    let index = match base.as_str() {
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        "gray" | "grey" => return Some(AnsiColor::Color16 { c16: 8 }),
+        _ => return None,
+    };
+
+    Some(AnsiColor::Color16 {
+        c16: if bright { index + 8 } else { index },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ccstatusline_widgets_map_to_segments() {
+        let json = serde_json::json!({
+            "lines": [
+                [
+                    { "type": "model", "color": "cyan", "bold": true },
+                    { "type": "separator" },
+                    { "type": "git-branch", "color": "brightMagenta", "backgroundColor": "bgBlue" },
+                    { "type": "git-changes", "color": "yellow" },
+                    { "type": "tokens-input" }
+                ],
+                [ { "type": "current-working-dir", "color": "hex:336699" } ]
+            ],
+            "defaultSeparator": "|"
+        });
+
+        let result = from_ccstatusline(&json);
+        let ids: Vec<SegmentId> = result
+            .config
+            .segments
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, vec![SegmentId::Model, SegmentId::Git, SegmentId::Cwd]);
+
+        let git = &result.config.segments[1];
+        assert_eq!(git.colors.text, Some(AnsiColor::Color16 { c16: 13 }));
+        assert_eq!(git.colors.background, Some(AnsiColor::Color16 { c16: 4 }));
+        assert_eq!(result.config.style.separator, " | ");
+        assert!(result.warnings.iter().any(|w| w.contains("tokens-input")));
+        assert_eq!(
+            result.config.rows,
+            vec![vec![SegmentId::Model, SegmentId::Git], vec![SegmentId::Cwd]]
+        );
+    }
+
+    #[test]
+    fn ccometixline_config_keeps_colors_and_reports_unknowns() {
+        let table: toml::Table = toml::from_str(
+            r#"
+            theme = "gruvbox"
+            [style]
+            mode = "plain"
+            separator = " | "
+
+            [[segments]]
+            id = "model"
+            enabled = true
+            icon = { plain = "M", nerd_font = "M" }
+            colors = { icon = { c256 = 208 }, text = "bright_yellow" }
+            styles = { text_bold = true }
+
+            [[segments]]
+            id = "git"
+            styles = { text_underline = "curly", underline_color = { c16 = 1 } }
+
+            [[segments]]
+            id = "cost"
+            styles = { text_underline = true, underline_color = "nope" }
+
+            [[segments]]
+            id = "quota"
+            enabled = true
+            "#,
+        )
+        .unwrap();
+
+        let result = from_ccometixline(&table);
+        let model = &result.config.segments[0];
+        assert_eq!(model.id, SegmentId::Model);
+        assert_eq!(model.icon.plain, "M");
+        assert_eq!(model.colors.icon, Some(AnsiColor::Color256 { c256: 208 }));
+        assert_eq!(model.colors.text, Some(AnsiColor::Color16 { c16: 11 }));
+        let git = &result.config.segments[1];
+        assert_eq!(git.styles.text_underline, Underline::Curly);
+        assert_eq!(
+            git.styles.underline_color,
+            Some(AnsiColor::Color16 { c16: 1 })
+        );
+        assert_eq!(
+            result.config.segments[2].styles.text_underline,
+            Underline::Single
+        );
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("segments.cost.styles.underline_color")));
+        assert!(!result.warnings.iter().any(|w| w.contains("text_underline")));
+        assert_eq!(result.config.style.mode, StyleMode::Plain);
+        assert_eq!(result.theme_name, "cometix-gruvbox");
+        assert!(result.warnings.iter().any(|w| w.contains("quota")));
+    }
+
+    #[test]
+    fn ccometixline_theme_names_become_valid_file_names() {
+        for (source, expected) in [
+            ("My Theme/v1.2", "cometix-my-theme-v1-2"),
+            ("../..", "cometix-imported"),
+            ("gruvbox", "cometix-gruvbox"),
+        ] {
+            let mut table = toml::Table::new();
+            table.insert("theme".to_string(), source.into());
+            let result = from_ccometixline(&table);
+            assert_eq!(result.theme_name, expected);
+            assert!(crate::ui::themes::is_valid_theme_name(&result.theme_name));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// The most recent statusline input received from Claude Code.
+//
+// Every normal run stores its stdin JSON here, so `--last-session` can render
+// the same segments where Claude Code does not drive the statusline, such as a
+// tmux status bar or a shell prompt.
+
+use super::InputData;
+use crate::core::transcript::write_atomic;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn path() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    Some(
+        home.join(".claude")
+            .join("eflowcodeline")
+            .join("cache")
+            .join("last_session.json"),
+    )
+}
+
+/// Remember the raw input of the current run. Failures are ignored: the cache
+/// must never break the statusline itself. The file is replaced atomically,
+/// as concurrent sessions and `--last-session` readers share it.
+pub fn save(raw: &str) {
+    if let Some(path) = path() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = write_atomic(&path, raw.as_bytes());
+    }
+}
+
+pub fn load() -> Result<InputData, Box<dyn std::error::Error>> {
+    let path = path().ok_or("Could not determine home directory")?;
+    let raw = fs::read_to_string(&path).map_err(|_| {
+        format!(
+            "No cached Claude Code session at {} (it is written whenever Claude Code renders the statusline)",
+            path.display()
+        )
+    })?;
+    Ok(serde_json::from_str(&raw)?)
+}
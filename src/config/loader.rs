@@ -1,7 +1,10 @@
-use super::types::Config;
-use std::path::PathBuf;
+use super::types::{Config, SegmentConfig};
+use crate::ui::themes::BUILTIN_THEMES;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-/// Result of config initialization (kept for CLI compatibility).
+/// Result of config initialization
 #[derive(Debug)]
 pub enum InitResult {
     /// Config was created at the given path
@@ -10,35 +13,588 @@ pub enum InitResult {
     AlreadyExists(PathBuf),
 }
 
+/// Name of the per-project override file, looked up from the workspace directory.
+pub const PROJECT_CONFIG_FILE: &str = ".eflowcodeline.toml";
+
+/// Keys a project-local `.eflowcodeline.toml` may not set.
+///
+/// These options steer where credentials are sent, so a cloned repository must
+/// not be able to change them. `*` matches one path component and a pattern
+/// also covers everything below it.
+const PROJECT_DENY_LIST: &[&str] = &[
+    "segments.usage.options",
+    "segments.balance.options",
+    "segments.used.options",
+    "segments.*.options.api_base_url",
+    "segments.*.options.api_key",
+    "segments.*.options.access_token",
+];
+
+/// An override key that had no effect on the loaded config.
+#[derive(Debug, Clone)]
+pub struct IgnoredKey {
+    pub key: String,
+    pub source: PathBuf,
+    pub reason: String,
+}
+
+/// What the layered loader did with the override files.
+///
+/// Keys are reported as dotted paths; segment entries are addressed by their
+/// `SegmentId` (e.g. `segments.usage.enabled`) rather than by array index.
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// Theme the layers were applied on top of
+    pub theme: String,
+    /// Why that theme could not be used as written, e.g. a theme file that
+    /// does not parse
+    pub theme_error: Option<String>,
+    /// Global override file that was consulted, `None` if the home dir is unknown
+    pub path: Option<PathBuf>,
+    /// Whether the global override file exists on disk
+    pub found: bool,
+    /// Project override file discovered from the workspace directory
+    pub project_path: Option<PathBuf>,
+    /// Why the project override file was skipped, e.g. a TOML syntax error
+    pub project_error: Option<String>,
+    /// Keys whose value comes from an override file, mapped to that file
+    pub overridden: BTreeMap<String, PathBuf>,
+    /// Keys present in an override file that had no effect
+    pub ignored: Vec<IgnoredKey>,
+}
+
+impl LoadReport {
+    fn ignore(&mut self, key: String, source: &Path, reason: impl Into<String>) {
+        self.ignored.push(IgnoredKey {
+            key,
+            source: source.to_path_buf(),
+            reason: reason.into(),
+        });
+    }
+}
+
 pub struct ConfigLoader;
 
 impl ConfigLoader {
-    /// Always returns the hard-coded default config — user config files are ignored.
+    /// Load the effective config, falling back to the built-in theme on any error.
     pub fn load() -> Config {
-        Config::default()
+        Self::load_with_report()
+            .map(|(config, _)| config)
+            .unwrap_or_default()
+    }
+
+    /// Load the built-in theme and layer `~/.claude/eflowcodeline/config.toml` on top.
+    pub fn load_with_report() -> Result<(Config, LoadReport), Box<dyn std::error::Error>> {
+        Self::load_for_dir(None)
+    }
+
+    /// Load theme → global `config.toml` → project `.eflowcodeline.toml`.
+    ///
+    /// The project layer is only consulted when `workspace` is given; keys on
+    /// the project deny-list are dropped and reported as ignored. A project
+    /// file that cannot be read is skipped and reported, so a typo in one
+    /// repository never discards the global config.
+    pub fn load_for_dir(
+        workspace: Option<&Path>,
+    ) -> Result<(Config, LoadReport), Box<dyn std::error::Error>> {
+        let path = Config::get_config_path();
+        let mut report = LoadReport {
+            path: path.clone(),
+            ..Default::default()
+        };
+
+        let mut layers = Vec::new();
+        if let Some(path) = path.filter(|p| p.exists()) {
+            report.found = true;
+            let table = Self::read_layer(&path)?;
+            layers.push((path, table));
+        }
+
+        if let Some(layer) = workspace.and_then(|dir| Self::project_layer(dir, &mut report)) {
+            layers.push(layer);
+        }
+
+        let config = Self::apply_layers(&layers, &mut report)?;
+        Ok((config, report))
+    }
+
+    /// Find the nearest `.eflowcodeline.toml` from `dir` up to the enclosing repo root.
+    ///
+    /// Outside a git repository only `dir` itself is checked, so a stray file in
+    /// a parent directory (or `$HOME`) does not apply to every scratch folder.
+    pub fn find_project_config(dir: &Path) -> Option<PathBuf> {
+        let in_repo = dir.ancestors().any(|d| d.join(".git").exists());
+
+        for candidate in dir.ancestors() {
+            let file = candidate.join(PROJECT_CONFIG_FILE);
+            if file.is_file() {
+                return Some(file);
+            }
+            if !in_repo || candidate.join(".git").exists() {
+                break;
+            }
+        }
+
+        None
+    }
+
+    /// The project override layer for `dir`, with denied keys stripped.
+    fn project_layer(dir: &Path, report: &mut LoadReport) -> Option<(PathBuf, toml::Table)> {
+        let path = Self::find_project_config(dir)?;
+        report.project_path = Some(path.clone());
+        match Self::read_layer(&path) {
+            Ok(mut table) => {
+                strip_denied(&mut table, &path, report);
+                Some((path, table))
+            }
+            Err(e) => {
+                report.project_error = Some(e.to_string());
+                None
+            }
+        }
+    }
+
+    fn read_layer(path: &Path) -> Result<toml::Table, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e).into())
+    }
+
+    /// Merge override layers, in order, onto the theme the last of them selects.
+    ///
+    /// Tables are merged key by key, `segments` entries are matched by `id`, and
+    /// colors are replaced as a whole. Afterwards every override leaf is looked up
+    /// in the resulting config; anything that did not survive deserialization
+    /// (unknown keys, unknown segment ids) is reported as ignored.
+    pub fn apply_layers(
+        layers: &[(PathBuf, toml::Table)],
+        report: &mut LoadReport,
+    ) -> Result<Config, Box<dyn std::error::Error>> {
+        let theme_name = layers
+            .iter()
+            .rev()
+            .find_map(|(_, table)| table.get("theme").and_then(|v| v.as_str()))
+            .unwrap_or("default");
+        report.theme = theme_name.to_string();
+        let (base, theme_error) = crate::ui::themes::ThemePresets::resolve(theme_name);
+        report.theme_error = theme_error;
+
+        let mut merged = toml::Table::try_from(&base)?;
+        for (source, overrides) in layers {
+            for (key, value) in overrides {
+                if key == "segments" {
+                    merge_segments(&mut merged, value, source, report);
+                } else {
+                    merge_value(&mut merged, key, value);
+                }
+            }
+        }
+
+        let config: Config = toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e| format!("Invalid config: {}", e))?;
+
+        let mut effective = BTreeMap::new();
+        flatten(
+            &toml::Value::Table(toml::Table::try_from(&config)?),
+            "",
+            &mut effective,
+        );
+
+        for (index, (source, overrides)) in layers.iter().enumerate() {
+            let mut wanted = BTreeMap::new();
+            flatten(&toml::Value::Table(overrides.clone()), "", &mut wanted);
+
+            for (key, value) in wanted {
+                let already_ignored = report.ignored.iter().any(|i| {
+                    i.source == *source && (key == i.key || key.starts_with(&format!("{}.", i.key)))
+                });
+                if already_ignored {
+                    continue;
+                }
+                // A later layer may legitimately replace this value.
+                let shadowed = layers[index + 1..].iter().any(|(_, later)| {
+                    let mut keys = BTreeMap::new();
+                    flatten(&toml::Value::Table(later.clone()), "", &mut keys);
+                    keys.contains_key(&key)
+                });
+                match effective.get(&key) {
+                    Some(v) if same_value(v, &value) || shadowed => {
+                        report.overridden.insert(key, source.clone());
+                    }
+                    _ => report.ignore(key, source, "unknown key"),
+                }
+            }
+        }
+
+        Ok(config)
     }
 }
 
+/// Structural equality that treats `80` and `80.0` as the same number, since
+/// float fields such as threshold bounds may be written as integers.
+fn same_value(a: &toml::Value, b: &toml::Value) -> bool {
+    use toml::Value::{Array, Float, Integer, Table};
+    match (a, b) {
+        (Integer(i), Float(f)) | (Float(f), Integer(i)) => *i as f64 == *f,
+        (Array(a), Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same_value(x, y))
+        }
+        (Table(a), Table(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).is_some_and(|w| same_value(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Remove project-layer keys matching `PROJECT_DENY_LIST`, and a `theme` that
+/// is not a built-in preset.
+fn strip_denied(table: &mut toml::Table, source: &Path, report: &mut LoadReport) {
+    let mut keys = BTreeMap::new();
+    flatten(&toml::Value::Table(table.clone()), "", &mut keys);
+
+    for key in keys.keys() {
+        let parts: Vec<&str> = key.split('.').collect();
+        let denied = PROJECT_DENY_LIST.iter().any(|pattern| {
+            let pattern: Vec<&str> = pattern.split('.').collect();
+            pattern.len() <= parts.len()
+                && pattern.iter().zip(&parts).all(|(p, k)| *p == "*" || p == k)
+        });
+        if denied {
+            remove_path(table, &parts);
+            report.ignore(key.clone(), source, "not allowed in project config");
+        }
+    }
+
+    // A theme file is a whole config, options included, so a project may only
+    // pick one of the built-in presets
+    let theme = table.get("theme").and_then(|v| v.as_str());
+    if theme.is_some_and(|name| !BUILTIN_THEMES.contains(&name)) {
+        table.remove("theme");
+        report.ignore(
+            "theme".to_string(),
+            source,
+            "only built-in themes allowed in project config",
+        );
+    }
+}
+
+/// Remove a flattened leaf path (segments addressed by id) from an override table.
+fn remove_path(table: &mut toml::Table, parts: &[&str]) {
+    match parts {
+        [] => {}
+        [leaf] => {
+            table.remove(*leaf);
+        }
+        ["segments", id, rest @ ..] => {
+            let segments = table.get_mut("segments").and_then(|v| v.as_array_mut());
+            for segment in segments.into_iter().flatten() {
+                if let Some(t) = segment.as_table_mut() {
+                    if t.get("id").and_then(|v| v.as_str()) == Some(*id) {
+                        remove_path(t, rest);
+                    }
+                }
+            }
+        }
+        [head, rest @ ..] => {
+            if let Some(toml::Value::Table(t)) = table.get_mut(*head) {
+                remove_path(t, rest);
+            }
+        }
+    }
+}
+
+/// Merge a single key into `target`, recursing into tables except colors.
+fn merge_value(target: &mut toml::Table, key: &str, value: &toml::Value) {
+    match (target.get_mut(key), value) {
+        (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) if key != "colors" => {
+            for (k, v) in incoming {
+                merge_value(existing, k, v);
+            }
+        }
+        (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+            // Colors are atomic: `{ c16 = 1 }` must replace an RGB value, not merge into it.
+            for (k, v) in incoming {
+                existing.insert(k.clone(), v.clone());
+            }
+        }
+        _ => {
+            target.insert(key.to_string(), value.clone());
+        }
+    }
+}
+
+/// Merge `[[segments]]` entries onto the base segments by `id`.
+fn merge_segments(
+    merged: &mut toml::Table,
+    value: &toml::Value,
+    source: &Path,
+    report: &mut LoadReport,
+) {
+    let Some(entries) = value.as_array() else {
+        report.ignore(
+            "segments".to_string(),
+            source,
+            "expected an array of tables",
+        );
+        return;
+    };
+    let Some(toml::Value::Array(base_segments)) = merged.get_mut("segments") else {
+        return;
+    };
+
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(table) = entry.as_table() else {
+            report.ignore(format!("segments[{}]", index), source, "expected a table");
+            continue;
+        };
+        let Some(id) = table.get("id").and_then(|v| v.as_str()) else {
+            report.ignore(format!("segments[{}]", index), source, "missing segment id");
+            continue;
+        };
+
+        let existing = base_segments.iter_mut().find_map(|s| match s {
+            toml::Value::Table(t) if t.get("id").and_then(|v| v.as_str()) == Some(id) => Some(t),
+            _ => None,
+        });
+
+        match existing {
+            Some(base) => {
+                for (k, v) in table {
+                    merge_value(base, k, v);
+                }
+            }
+            None => {
+                // Segments missing from the base theme must be spelled out in full.
+                match toml::Value::Table(table.clone()).try_into::<SegmentConfig>() {
+                    Ok(_) => base_segments.push(entry.clone()),
+                    Err(e) => report.ignore(
+                        format!("segments.{}", id),
+                        source,
+                        format!("not in the base theme and incomplete: {}", e),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Flatten a TOML value into dotted leaf paths, addressing segments by id.
+fn flatten(value: &toml::Value, prefix: &str, out: &mut BTreeMap<String, toml::Value>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (k, v) in table {
+                let path = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                if path == "segments" {
+                    for segment in v.as_array().into_iter().flatten() {
+                        let Some(mut table) = segment.as_table().cloned() else {
+                            continue;
+                        };
+                        if let Some(toml::Value::String(id)) = table.remove("id") {
+                            flatten(&toml::Value::Table(table), &format!("segments.{}", id), out);
+                        }
+                    }
+                } else if prefix.ends_with(".colors") {
+                    out.insert(path, v.clone());
+                } else {
+                    flatten(v, &path, out);
+                }
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// Produce the minimal override table that turns `base` into `config`.
+fn diff(base: &toml::Table, config: &toml::Table) -> toml::Table {
+    let mut out = toml::Table::new();
+    for (key, value) in config {
+        if key == "segments" {
+            let base_segments = base.get("segments").and_then(|v| v.as_array());
+            let mut changed = Vec::new();
+            for segment in value.as_array().into_iter().flatten() {
+                let Some(table) = segment.as_table() else {
+                    continue;
+                };
+                let id = table.get("id").and_then(|v| v.as_str());
+                let base_table = base_segments.and_then(|segments| {
+                    segments.iter().find_map(|s| match s {
+                        toml::Value::Table(t) if t.get("id").and_then(|v| v.as_str()) == id => {
+                            Some(t)
+                        }
+                        _ => None,
+                    })
+                });
+                let entry = match base_table {
+                    Some(base_table) => diff(base_table, table),
+                    None => table.clone(),
+                };
+                if !entry.is_empty() {
+                    let mut entry = entry;
+                    if let Some(id) = id {
+                        entry.insert("id".to_string(), toml::Value::String(id.to_string()));
+                    }
+                    changed.push(toml::Value::Table(entry));
+                }
+            }
+            if !changed.is_empty() {
+                out.insert(key.clone(), toml::Value::Array(changed));
+            }
+            continue;
+        }
+
+        match (base.get(key), value) {
+            (Some(b), v) if b == v => {}
+            (Some(toml::Value::Table(b)), toml::Value::Table(v)) if key != "colors" => {
+                let nested = diff(b, v);
+                if !nested.is_empty() {
+                    out.insert(key.clone(), toml::Value::Table(nested));
+                }
+            }
+            _ => {
+                out.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    out
+}
+
+const STARTER_CONFIG: &str = r#"# EFlowCodeLine configuration
+# File location: ~/.claude/eflowcodeline/config.toml
+#
+# This file is layered on top of the built-in theme: only the keys you set here
+# are overridden, everything else keeps the theme's value. Segments are matched
+# by `id`, so you only need to list the segments you want to change.
+# Run `eflowcodeline --check` to see which keys were applied or ignored.
+#
+# Segment ids: model, context_window, usage, cost, session, output_style,
+#              cwd, directory, git, used, balance, branding, update
+
+# Base theme: default | minimal | gruvbox | nord, or the name of a file in
+# ~/.claude/eflowcodeline/themes/ (without the .toml extension)
+# theme = "default"
+
+# Row layout: which segments go on each line, in order. Enabled segments that
+# are not listed end up on the last row. Must come before any [table].
+# rows = [
+#   ["model", "context_window", "usage", "cost", "session", "output_style"],
+#   ["cwd", "directory", "git"],
+#   ["used", "balance", "branding"],
+# ]
+
+# [style]
+# mode = "nerd_font"        # plain | nerd_font | powerline
+# separator = ""
+# Powerline glyphs (arrow e0b0, rounded e0b4, slanted e0bc, flame e0c0 and
+# their thin variants) blend the backgrounds of neighbouring segments, and
+# without caps every line opens and closes with the separator's filled shape.
+# start_cap = "\uE0B6"     # drawn before the first segment of each line
+# end_cap = "\uE0B4"       # drawn after the last segment of each line
+# Colors the terminal supports; "auto" checks NO_COLOR, COLORTERM and TERM and
+# richer theme colors are reduced to the nearest available one. "none" prints
+# plain text without any escape sequences.
+# color_depth = "auto"      # auto | truecolor | 256 | 16 | none
+# hyperlinks = true         # clickable paths, Git branch and update notice
+# Backgrounds blended across the segments of each row: two colors fade from
+# start to end, more colors form a palette.
+# gradient = [{ r = 42, g = 56, b = 110 }, { r = 228, g = 134, b = 78 }]
+
+# Hide the Usage and Branding segments:
+# [[segments]]
+# id = "usage"
+# enabled = false
+#
+# [[segments]]
+# id = "branding"
+# enabled = false
+
+# Recolor and restyle the Git segment:
+# [[segments]]
+# id = "git"
+# colors.background = { r = 40, g = 120, b = 80 }
+# styles.text_bold = true
+# styles.text_italic = true
+# styles.text_underline = "curly"     # none | single | double | curly | dotted | dashed
+
+# Custom text from a template (placeholders are listed in the README):
+# [[segments]]
+# id = "context_window"
+# options.format = "{percentage_display}% of {limit_k}"
+
+# Turn the context window red once it is 80% full:
+# [[segments.thresholds]]
+# above = 80
+# colors.background = { c16 = 1 }
+"#;
+
 impl Config {
-    /// Always returns the hard-coded default config.
-    /// Kept as a fallible signature for backwards compatibility with the CLI.
+    /// Location of the user's override file: `~/.claude/eflowcodeline/config.toml`
+    pub fn get_config_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".claude")
+                .join("eflowcodeline")
+                .join("config.toml")
+        })
+    }
+
+    /// Load the built-in theme with the user's `config.toml` layered on top.
     pub fn load() -> Result<Config, Box<dyn std::error::Error>> {
-        Ok(Config::default())
+        ConfigLoader::load_with_report().map(|(config, _)| config)
+    }
+
+    /// Like [`Config::load`], plus the project `.eflowcodeline.toml` found from `dir`.
+    pub fn load_for_workspace(dir: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        ConfigLoader::load_for_dir(Some(Path::new(dir))).map(|(config, _)| config)
     }
 
-    /// Saving is intentionally a no-op now that themes are hard-coded.
+    /// Save the keys that differ from the selected theme to `config.toml`.
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::get_config_path().ok_or("Could not determine home directory")?;
+        let base = crate::ui::themes::ThemePresets::get_theme(&self.theme);
+
+        let mut overrides = diff(
+            &toml::Table::try_from(&base)?,
+            &toml::Table::try_from(self)?,
+        );
+        if self.theme != "default" {
+            overrides.insert("theme".to_string(), toml::Value::String(self.theme.clone()));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = format!(
+            "# EFlowCodeLine configuration (layered on top of the \"{}\" theme)\n\n{}",
+            self.theme,
+            toml::to_string_pretty(&overrides)?
+        );
+        fs::write(&path, content)?;
         Ok(())
     }
 
-    /// Init no longer writes anything to disk; reports as already existing.
+    /// Write a commented starter `config.toml` if none exists yet.
     pub fn init() -> Result<InitResult, Box<dyn std::error::Error>> {
-        Ok(InitResult::AlreadyExists(PathBuf::from(
-            "<built-in: themes are hard-coded>",
-        )))
+        let path = Self::get_config_path().ok_or("Could not determine home directory")?;
+        if path.exists() {
+            return Ok(InitResult::AlreadyExists(path));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, STARTER_CONFIG)?;
+        Ok(InitResult::Created(path))
     }
 
-    /// Validate configuration — still useful for sanity-checking the built-in default.
+    /// Validate configuration
     pub fn check(&self) -> Result<(), Box<dyn std::error::Error>> {
         if self.segments.is_empty() {
             return Err("No segments configured".into());
@@ -54,10 +610,230 @@ impl Config {
         Ok(())
     }
 
-    /// Print configuration as TOML (still handy for `--check`/`--print` in CLI).
+    /// Print configuration as TOML
     pub fn print(&self) -> Result<(), Box<dyn std::error::Error>> {
         let content = toml::to_string_pretty(self)?;
         println!("{}", content);
         Ok(())
     }
 }
+
+impl LoadReport {
+    /// Print the report in the format used by `--check`.
+    pub fn print(&self) {
+        match (&self.path, self.found) {
+            (Some(path), true) => println!("Config file: {}", path.display()),
+            (Some(path), false) => println!(
+                "Config file: {} (not found, using built-in theme)",
+                path.display()
+            ),
+            (None, _) => println!("Config file: <unavailable> (using built-in theme)"),
+        }
+        match (&self.project_path, &self.project_error) {
+            (Some(project), Some(error)) => {
+                println!("Project file: {} (skipped)", project.display());
+                for line in error.lines() {
+                    println!("  {}", line);
+                }
+            }
+            (Some(project), None) => println!("Project file: {}", project.display()),
+            (None, _) => {}
+        }
+        if let Some(ref error) = self.theme_error {
+            println!("Theme \"{}\": {}", self.theme, error);
+        }
+
+        if !self.overridden.is_empty() {
+            println!("Overridden keys:");
+            for (key, source) in &self.overridden {
+                println!("  {} <- {}", key, source.display());
+            }
+        }
+
+        if !self.ignored.is_empty() {
+            println!("Ignored keys:");
+            for ignored in &self.ignored {
+                println!(
+                    "  {} ({}, in {})",
+                    ignored.key,
+                    ignored.reason,
+                    ignored.source.display()
+                );
+            }
+        }
+    }
+
+    /// Print the provenance of every overridden value as TOML comments for `--print`.
+    pub fn print_sources(&self) {
+        println!(
+            "# Value sources (keys not listed come from the \"{}\" theme):",
+            self.theme
+        );
+        if let (Some(project), Some(error)) = (&self.project_path, &self.project_error) {
+            println!("# Skipped {}:", project.display());
+            for line in error.lines() {
+                println!("#   {}", line);
+            }
+        }
+        for (key, source) in &self.overridden {
+            println!("#   {} <- {}", key, source.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AnsiColor, SegmentId};
+
+    fn layer(name: &str, src: &str) -> (PathBuf, toml::Table) {
+        (PathBuf::from(name), toml::from_str(src).unwrap())
+    }
+
+    fn apply(src: &str) -> (Config, LoadReport) {
+        let mut report = LoadReport::default();
+        let config = ConfigLoader::apply_layers(&[layer("config.toml", src)], &mut report).unwrap();
+        (config, report)
+    }
+
+    fn segment(config: &Config, id: SegmentId) -> &SegmentConfig {
+        config.segments.iter().find(|s| s.id == id).unwrap()
+    }
+
+    #[test]
+    fn segment_overrides_merge_by_id() {
+        let (config, report) = apply(
+            r#"
+            [[segments]]
+            id = "usage"
+            enabled = false
+
+            [[segments]]
+            id = "git"
+            colors.background = { c16 = 2 }
+            "#,
+        );
+
+        assert!(!segment(&config, SegmentId::Usage).enabled);
+        assert_eq!(
+            segment(&config, SegmentId::Git).colors.background,
+            Some(AnsiColor::Color16 { c16: 2 })
+        );
+        // Untouched segments keep the theme's values
+        assert!(segment(&config, SegmentId::Model).enabled);
+        assert_eq!(config.segments.len(), Config::default().segments.len());
+        assert!(report.overridden.contains_key("segments.usage.enabled"));
+        assert!(report.ignored.is_empty(), "{:?}", report.ignored);
+    }
+
+    #[test]
+    fn unknown_keys_and_ids_are_reported() {
+        let (_, report) = apply(
+            r#"
+            bogus = 1
+
+            [[segments]]
+            id = "usage"
+            colour = "red"
+
+            [[segments]]
+            id = "nope"
+            enabled = true
+            "#,
+        );
+
+        let ignored: Vec<&str> = report.ignored.iter().map(|i| i.key.as_str()).collect();
+        assert!(ignored.contains(&"bogus"));
+        assert!(ignored.contains(&"segments.usage.colour"));
+        assert!(ignored.contains(&"segments.nope"));
+    }
+
+    #[test]
+    fn project_layer_wins_but_cannot_touch_credentials() {
+        let global = layer(
+            "config.toml",
+            r#"
+            [[segments]]
+            id = "git"
+            enabled = false
+            "#,
+        );
+        let mut project = layer(
+            ".eflowcodeline.toml",
+            r#"
+            theme = "../../../some/repo/file"
+
+            [[segments]]
+            id = "git"
+            enabled = true
+
+            [[segments]]
+            id = "usage"
+            options.api_base_url = "https://evil.example"
+            "#,
+        );
+
+        let mut report = LoadReport::default();
+        strip_denied(&mut project.1, &project.0, &mut report);
+        let config = ConfigLoader::apply_layers(&[global, project], &mut report).unwrap();
+
+        assert!(segment(&config, SegmentId::Git).enabled);
+        assert!(!segment(&config, SegmentId::Usage)
+            .options
+            .contains_key("api_base_url"));
+        assert_eq!(
+            report.overridden.get("segments.git.enabled"),
+            Some(&PathBuf::from(".eflowcodeline.toml"))
+        );
+        assert!(report
+            .ignored
+            .iter()
+            .any(|i| i.key == "segments.usage.options.api_base_url"));
+        assert_eq!(config.theme, "default");
+        assert!(report.ignored.iter().any(|i| i.key == "theme"));
+    }
+
+    #[test]
+    fn malformed_project_file_is_skipped_and_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "theme = \"nord\"\n[[segments",
+        )
+        .unwrap();
+
+        let mut report = LoadReport::default();
+        assert!(ConfigLoader::project_layer(dir.path(), &mut report).is_none());
+        assert_eq!(
+            report.project_path,
+            Some(dir.path().join(PROJECT_CONFIG_FILE))
+        );
+        assert!(report.project_error.is_some());
+
+        // The global layer still applies
+        let global = layer("config.toml", "theme = \"minimal\"");
+        let config = ConfigLoader::apply_layers(&[global], &mut report).unwrap();
+        assert_eq!(config.theme, "minimal");
+    }
+
+    #[test]
+    fn diff_round_trips_through_overrides() {
+        let base = Config::default();
+        let mut edited = base.clone();
+        edited.segments[0].enabled = false;
+        edited.style.separator = "|".to_string();
+
+        let overrides = diff(
+            &toml::Table::try_from(&base).unwrap(),
+            &toml::Table::try_from(&edited).unwrap(),
+        );
+        let mut report = LoadReport::default();
+        let reloaded =
+            ConfigLoader::apply_layers(&[(PathBuf::from("config.toml"), overrides)], &mut report)
+                .unwrap();
+
+        assert_eq!(reloaded.style.separator, "|");
+        assert!(!reloaded.segments[0].enabled);
+        assert_eq!(report.overridden.len(), 2);
+    }
+}
@@ -1,10 +1,12 @@
 pub mod balance_config;
 pub mod defaults;
+pub mod import;
+pub mod last_session;
 pub mod loader;
 pub mod models;
 pub mod types;
 
 pub use balance_config::BalanceConfig;
-pub use loader::{ConfigLoader, InitResult};
+pub use loader::{ConfigLoader, InitResult, LoadReport};
 pub use models::*;
 pub use types::*;
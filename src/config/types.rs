@@ -5,6 +5,10 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub style: StyleConfig,
+    /// Segment ids per statusline row, in display order. Enabled segments not
+    /// listed in any row are appended to the last row; empty means one row.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<Vec<SegmentId>>,
     pub segments: Vec<SegmentConfig>,
     pub theme: String,
 }
@@ -15,6 +19,47 @@ pub struct Config {
 pub struct StyleConfig {
     pub mode: StyleMode,
     pub separator: String,
+    /// Glyph drawn before the first segment of each line, in its background
+    /// color (e.g. "\u{e0b6}" for a rounded start). When empty, powerline
+    /// separators supply a matching glyph.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub start_cap: String,
+    /// Glyph drawn after the last segment of each line.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub end_cap: String,
+    /// Colors the terminal can display; richer colors are downsampled.
+    #[serde(default)]
+    pub color_depth: ColorDepth,
+    /// Make paths, the Git branch and the update notice clickable (OSC 8).
+    #[serde(default = "default_true")]
+    pub hyperlinks: bool,
+    /// Background colors interpolated across the segments of each row, in
+    /// place of their own backgrounds: two colors for a start/end fade, more
+    /// for a palette. Empty keeps the per-segment backgrounds.
+    #[serde(default)]
+    pub gradient: Vec<AnsiColor>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Color capability of the terminal the statusline is rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ColorDepth {
+    /// Detect from NO_COLOR, COLORTERM and TERM.
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "truecolor")]
+    Truecolor,
+    #[serde(rename = "256")]
+    Color256,
+    #[serde(rename = "16")]
+    Color16,
+    /// Monochrome: no colors, styles or other escape sequences at all.
+    #[serde(rename = "none")]
+    None,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -33,6 +78,47 @@ pub struct SegmentConfig {
     pub colors: ColorConfig,
     pub styles: TextStyleConfig,
     pub options: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thresholds: Vec<ThresholdRule>,
+    #[serde(default, skip_serializing_if = "Align::is_left")]
+    pub align: Align,
+    /// When a row does not fit, segments with a lower priority are compacted
+    /// and then hidden first; segments without a priority are never hidden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// Hide the segment when the available width is below this many columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_width: Option<usize>,
+}
+
+/// Horizontal group a segment belongs to within its row.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    pub fn is_left(&self) -> bool {
+        *self == Align::Left
+    }
+}
+
+/// Dynamic color rule: when the segment's metric is `>= above` and/or `< below`,
+/// the rule's colors replace the segment's static colors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdRule {
+    /// Metadata key to compare; defaults to the segment's main metric
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub above: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below: Option<f64>,
+    pub colors: ColorConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,16 +127,44 @@ pub struct IconConfig {
     pub nerd_font: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColorConfig {
     pub icon: Option<AnsiColor>,
     pub text: Option<AnsiColor>,
     pub background: Option<AnsiColor>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct TextStyleConfig {
     pub text_bold: bool,
+    #[serde(default)]
+    pub text_italic: bool,
+    #[serde(default)]
+    pub text_dim: bool,
+    #[serde(default)]
+    pub text_underline: Underline,
+    /// Underline color; the text color when unset. Not every terminal can
+    /// color underlines separately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underline_color: Option<AnsiColor>,
+    #[serde(default)]
+    pub text_strikethrough: bool,
+    /// Swap the text and background colors.
+    #[serde(default)]
+    pub text_inverse: bool,
+}
+
+/// Underline shape. Terminals without styled underlines draw a plain one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Underline {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -251,10 +365,19 @@ impl Config {
         // Compare style config
         if self.style.mode != theme_preset.style.mode
             || self.style.separator != theme_preset.style.separator
+            || self.style.start_cap != theme_preset.style.start_cap
+            || self.style.end_cap != theme_preset.style.end_cap
+            || self.style.color_depth != theme_preset.style.color_depth
+            || self.style.hyperlinks != theme_preset.style.hyperlinks
+            || self.style.gradient != theme_preset.style.gradient
         {
             return false;
         }
 
+        if self.rows != theme_preset.rows {
+            return false;
+        }
+
         // Compare segments count and order
         if self.segments.len() != theme_preset.segments.len() {
             return false;
@@ -270,6 +393,49 @@ impl Config {
         true
     }
 
+    /// Move a segment one step earlier or later in the row layout. A segment at
+    /// the edge of a row moves to the end (or start) of the neighbouring row.
+    /// `segments` is reordered to match so the list follows the layout.
+    /// Returns the segment's row index, or `None` if it is not in `rows`.
+    pub fn move_in_rows(&mut self, id: SegmentId, up: bool) -> Option<usize> {
+        let (row, pos) = self
+            .rows
+            .iter()
+            .enumerate()
+            .find_map(|(r, ids)| ids.iter().position(|i| *i == id).map(|p| (r, p)))?;
+
+        let new_row = match (up, pos) {
+            (true, 0) if row > 0 => {
+                self.rows[row].remove(pos);
+                self.rows[row - 1].push(id);
+                row - 1
+            }
+            (true, p) if p > 0 => {
+                self.rows[row].swap(p, p - 1);
+                row
+            }
+            (false, p) if p + 1 < self.rows[row].len() => {
+                self.rows[row].swap(p, p + 1);
+                row
+            }
+            (false, _) if row + 1 < self.rows.len() => {
+                self.rows[row].remove(pos);
+                self.rows[row + 1].insert(0, id);
+                row + 1
+            }
+            _ => row,
+        };
+
+        let order: Vec<SegmentId> = self.rows.iter().flatten().copied().collect();
+        self.segments.sort_by_key(|s| {
+            order
+                .iter()
+                .position(|id| *id == s.id)
+                .unwrap_or(usize::MAX)
+        });
+        Some(new_row)
+    }
+
     /// Check if current config has been modified from the selected theme
     pub fn is_modified_from_theme(&self) -> bool {
         !self.matches_theme(&self.theme)
@@ -284,8 +450,12 @@ impl Config {
             && self.color_matches(&current.colors.icon, &preset.colors.icon)
             && self.color_matches(&current.colors.text, &preset.colors.text)
             && self.color_matches(&current.colors.background, &preset.colors.background)
-            && current.styles.text_bold == preset.styles.text_bold
+            && current.styles == preset.styles
             && current.options == preset.options
+            && current.thresholds == preset.thresholds
+            && current.align == preset.align
+            && current.priority == preset.priority
+            && current.min_width == preset.min_width
     }
 
     /// Compare two optional colors for equality
@@ -417,4 +587,26 @@ pub struct TranscriptEntry {
     #[serde(rename = "parentUuid")]
     pub parent_uuid: Option<String>,
     pub summary: Option<String>,
+    /// Written by a Task-tool subagent rather than the main thread
+    #[serde(rename = "isSidechain", default)]
+    pub is_sidechain: bool,
+    #[serde(rename = "agentId")]
+    pub agent_id: Option<String>,
+    /// `compact_boundary` on the system entry written by a compaction
+    pub subtype: Option<String>,
+    #[serde(rename = "compactMetadata")]
+    pub compact_metadata: Option<CompactMetadata>,
+    /// The user message carrying the summary a compaction left behind
+    #[serde(rename = "isCompactSummary", default)]
+    pub is_compact_summary: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CompactMetadata {
+    pub trigger: Option<String>,
+    #[serde(rename = "preTokens")]
+    pub pre_tokens: Option<u32>,
+    /// Not on every boundary; the size is then estimated from the summary
+    #[serde(rename = "postTokens")]
+    pub post_tokens: Option<u32>,
 }
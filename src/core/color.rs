@@ -0,0 +1,264 @@
+// Terminal color depth detection and downsampling.
+//
+// Themes use whatever colors their author picked (the default theme is all
+// RGB); at render time they are reduced to what the terminal can show:
+//
+//   NO_COLOR set and non-empty             -> none (monochrome)
+//   COLORTERM=truecolor or 24bit          -> truecolor
+//   TERM=*-direct, *truecolor or *24bit    -> truecolor
+//   TERM=*256color*                        -> 256 colors
+//   TERM unset                             -> truecolor
+//   any other TERM (linux, screen, xterm)  -> 16 colors
+//
+// `style.color_depth` skips the detection. Theme gradients are blended here
+// too, before any downsampling.
+
+use crate::config::{AnsiColor, ColorDepth};
+
+/// Standard xterm values of the 16 basic colors.
+const PALETTE_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel levels of the 6x6x6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Resolve `Auto` from the environment; explicit depths are kept as is.
+pub fn detect_depth(configured: ColorDepth) -> ColorDepth {
+    if configured != ColorDepth::Auto {
+        return configured;
+    }
+    if std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+        return ColorDepth::None;
+    }
+    depth_from_env(
+        std::env::var("COLORTERM").ok().as_deref(),
+        std::env::var("TERM").ok().as_deref(),
+    )
+}
+
+fn depth_from_env(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+    if matches!(colorterm, Some("truecolor" | "24bit")) {
+        return ColorDepth::Truecolor;
+    }
+    match term {
+        None | Some("") => ColorDepth::Truecolor,
+        Some(term)
+            if term.ends_with("-direct")
+                || term.contains("truecolor")
+                || term.contains("24bit") =>
+        {
+            ColorDepth::Truecolor
+        }
+        Some(term) if term.contains("256color") => ColorDepth::Color256,
+        Some(_) => ColorDepth::Color16,
+    }
+}
+
+/// The closest color `depth` can display.
+pub fn downsample(color: &AnsiColor, depth: ColorDepth) -> AnsiColor {
+    match (depth, color) {
+        (ColorDepth::Color256, AnsiColor::Rgb { r, g, b }) => AnsiColor::Color256 {
+            c256: rgb_to_256(*r, *g, *b),
+        },
+        (ColorDepth::Color16, AnsiColor::Rgb { r, g, b }) => AnsiColor::Color16 {
+            c16: rgb_to_16(*r, *g, *b),
+        },
+        (ColorDepth::Color16, AnsiColor::Color256 { c256 }) if *c256 >= 16 => {
+            let (r, g, b) = xterm_rgb(*c256);
+            AnsiColor::Color16 {
+                c16: rgb_to_16(r, g, b),
+            }
+        }
+        (ColorDepth::Color16, AnsiColor::Color256 { c256 }) => AnsiColor::Color16 { c16: *c256 },
+        _ => color.clone(),
+    }
+}
+
+/// `count` colors evenly spread along the stops of a gradient, from the first
+/// stop to the last. Stops of any kind are blended in RGB.
+pub fn gradient(stops: &[AnsiColor], count: usize) -> Vec<AnsiColor> {
+    let stops: Vec<(u8, u8, u8)> = stops.iter().map(to_rgb).collect();
+    if stops.is_empty() {
+        return Vec::new();
+    }
+    let spans = (stops.len() - 1) as f64;
+
+    (0..count)
+        .map(|i| {
+            let t = if count > 1 {
+                i as f64 / (count - 1) as f64 * spans
+            } else {
+                0.0
+            };
+            let from = (t.floor() as usize).min(stops.len() - 1);
+            let to = (from + 1).min(stops.len() - 1);
+            let frac = t - from as f64;
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * frac).round() as u8;
+            let (a, b) = (stops[from], stops[to]);
+            AnsiColor::Rgb {
+                r: mix(a.0, b.0),
+                g: mix(a.1, b.1),
+                b: mix(a.2, b.2),
+            }
+        })
+        .collect()
+}
+
+fn to_rgb(color: &AnsiColor) -> (u8, u8, u8) {
+    match color {
+        AnsiColor::Color16 { c16 } => PALETTE_16[(*c16 & 15) as usize],
+        AnsiColor::Color256 { c256 } => xterm_rgb(*c256),
+        AnsiColor::Rgb { r, g, b } => (*r, *g, *b),
+    }
+}
+
+/// Nearest entry of the color cube or grayscale ramp. The first 16 indices
+/// are skipped since terminals recolor them freely.
+fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray = if average > 238 {
+        23
+    } else {
+        (average.saturating_sub(3) / 10) as u8
+    };
+    let gray_level = 8 + 10 * gray;
+
+    let cube_rgb = (
+        CUBE_LEVELS[ri as usize],
+        CUBE_LEVELS[gi as usize],
+        CUBE_LEVELS[bi as usize],
+    );
+    if distance((r, g, b), (gray_level, gray_level, gray_level)) < distance((r, g, b), cube_rgb) {
+        232 + gray
+    } else {
+        cube
+    }
+}
+
+fn rgb_to_16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16u8)
+        .min_by_key(|&i| distance((r, g, b), PALETTE_16[i as usize]))
+        .unwrap_or(7)
+}
+
+/// RGB value of a 256-color index.
+fn xterm_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => PALETTE_16[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Squared distance weighted for perceived brightness ("redmean").
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let mean = (a.0 as i32 + b.0 as i32) / 2;
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    ((((512 + mean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean) * db * db) >> 8)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_follows_colorterm_then_term() {
+        assert_eq!(
+            depth_from_env(Some("truecolor"), Some("screen")),
+            ColorDepth::Truecolor
+        );
+        assert_eq!(
+            depth_from_env(None, Some("tmux-256color")),
+            ColorDepth::Color256
+        );
+        assert_eq!(depth_from_env(None, Some("linux")), ColorDepth::Color16);
+        assert_eq!(depth_from_env(None, None), ColorDepth::Truecolor);
+        assert_eq!(detect_depth(ColorDepth::Color16), ColorDepth::Color16);
+    }
+
+    #[test]
+    fn rgb_maps_to_nearest_palette_entry() {
+        let red = AnsiColor::Rgb { r: 255, g: 0, b: 0 };
+        assert_eq!(
+            downsample(&red, ColorDepth::Color256),
+            AnsiColor::Color256 { c256: 196 }
+        );
+        assert_eq!(
+            downsample(&red, ColorDepth::Color16),
+            AnsiColor::Color16 { c16: 9 }
+        );
+
+        let gray = AnsiColor::Rgb {
+            r: 128,
+            g: 128,
+            b: 128,
+        };
+        assert_eq!(
+            downsample(&gray, ColorDepth::Color256),
+            AnsiColor::Color256 { c256: 244 }
+        );
+        assert_eq!(
+            downsample(&AnsiColor::Color256 { c256: 21 }, ColorDepth::Color16),
+            AnsiColor::Color16 { c16: 4 }
+        );
+        assert_eq!(downsample(&red, ColorDepth::Truecolor), red);
+    }
+
+    #[test]
+    fn gradient_spreads_over_every_stop() {
+        let rgb = |r, g, b| AnsiColor::Rgb { r, g, b };
+        let stops = [
+            rgb(0, 0, 0),
+            rgb(200, 100, 0),
+            AnsiColor::Color16 { c16: 15 },
+        ];
+        assert_eq!(
+            gradient(&stops, 5),
+            vec![
+                rgb(0, 0, 0),
+                rgb(100, 50, 0),
+                rgb(200, 100, 0),
+                rgb(228, 178, 128),
+                rgb(255, 255, 255),
+            ]
+        );
+        assert_eq!(gradient(&stops[..2], 1), vec![rgb(0, 0, 0)]);
+        assert!(gradient(&[], 3).is_empty());
+    }
+}
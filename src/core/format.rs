@@ -0,0 +1,106 @@
+// Segment text templates.
+//
+// A segment's `format` option replaces the fixed ` icon primary secondary `
+// text with a template rendered against `SegmentData.metadata`:
+//
+//   format = "{percentage_display}% of {limit_k}"
+//
+// - `{key}` inserts a metadata value; `{primary}` and `{secondary}` are always
+//   available and hold the segment's default text.
+// - `{key|fallback}` inserts `fallback` when `key` is missing or empty.
+// - `{{` and `}}` produce literal braces.
+//
+// If a placeholder without a fallback cannot be resolved, the template is
+// abandoned and the segment keeps its default text.
+
+use super::segments::SegmentData;
+
+/// Render `template` against `data`, or `None` if a required key is missing
+/// or the template is malformed.
+pub fn render_template(template: &str, data: &SegmentData) -> Option<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        ch => placeholder.push(ch),
+                    }
+                }
+                let (key, fallback) = match placeholder.split_once('|') {
+                    Some((key, fallback)) => (key.trim(), Some(fallback)),
+                    None => (placeholder.trim(), None),
+                };
+                match (lookup(key, data), fallback) {
+                    (Some(value), _) => output.push_str(value),
+                    (None, Some(fallback)) => output.push_str(fallback),
+                    (None, None) => return None,
+                }
+            }
+            '}' => return None,
+            _ => output.push(c),
+        }
+    }
+
+    Some(output)
+}
+
+fn lookup<'a>(key: &str, data: &'a SegmentData) -> Option<&'a str> {
+    let value = match key {
+        "primary" => data.primary.as_str(),
+        "secondary" => data.secondary.as_str(),
+        _ => data.metadata.get(key)?.as_str(),
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn data() -> SegmentData {
+        SegmentData {
+            primary: "42% · 84k tokens".to_string(),
+            secondary: String::new(),
+            metadata: HashMap::from([
+                ("percentage_display".to_string(), "42".to_string()),
+                ("limit_k".to_string(), "200k".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn placeholders_resolve_from_metadata() {
+        assert_eq!(
+            render_template("{percentage_display}% of {limit_k}", &data()).as_deref(),
+            Some("42% of 200k")
+        );
+        assert_eq!(
+            render_template("[{primary}] {{raw}}", &data()).as_deref(),
+            Some("[42% · 84k tokens] {raw}")
+        );
+    }
+
+    #[test]
+    fn missing_keys_use_fallback_or_abandon_template() {
+        assert_eq!(
+            render_template("{group|default}: {secondary|-}", &data()).as_deref(),
+            Some("default: -")
+        );
+        assert_eq!(render_template("{group}", &data()), None);
+        assert_eq!(render_template("{limit_k", &data()), None);
+    }
+}
@@ -0,0 +1,86 @@
+// Machine-readable output of collected segment data (`--format json`):
+//
+//   {
+//     "version": 1,
+//     "segments": [
+//       { "id": "model", "primary": "Opus 4.1", "secondary": "", "metadata": {} }
+//     ]
+//   }
+//
+// Segments appear in config order and only when enabled. New fields may be
+// added; changing or removing an existing one bumps `version`.
+
+use super::segments::SegmentData;
+use crate::config::{SegmentConfig, SegmentId};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Schema version of the JSON output.
+pub const JSON_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct JsonOutput<'a> {
+    pub version: u32,
+    pub segments: Vec<JsonSegment<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonSegment<'a> {
+    pub id: SegmentId,
+    pub primary: &'a str,
+    pub secondary: &'a str,
+    /// Sorted so the output is stable between runs
+    pub metadata: BTreeMap<&'a str, &'a str>,
+}
+
+impl<'a> JsonOutput<'a> {
+    pub fn new(segments: &'a [(SegmentConfig, SegmentData)]) -> Self {
+        Self {
+            version: JSON_VERSION,
+            segments: segments
+                .iter()
+                .filter(|(config, _)| config.enabled)
+                .map(|(config, data)| JsonSegment {
+                    id: config.id,
+                    primary: &data.primary,
+                    secondary: &data.secondary,
+                    metadata: data
+                        .metadata
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), v.as_str()))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Render collected segments as a single-line JSON document.
+pub fn render_json(segments: &[(SegmentConfig, SegmentData)]) -> String {
+    serde_json::to_string(&JsonOutput::new(segments)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn output_has_version_and_sorted_metadata() {
+        let config = crate::ui::themes::theme_default::model_segment();
+        let data = SegmentData {
+            primary: "Opus".to_string(),
+            secondary: String::new(),
+            metadata: HashMap::from([
+                ("model_id".to_string(), "claude-opus".to_string()),
+                ("display_name".to_string(), "Opus".to_string()),
+            ]),
+        };
+
+        let json = render_json(&[(config, data)]);
+        assert_eq!(
+            json,
+            r#"{"version":1,"segments":[{"id":"model","primary":"Opus","secondary":"","metadata":{"display_name":"Opus","model_id":"claude-opus"}}]}"#
+        );
+    }
+}
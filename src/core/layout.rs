@@ -0,0 +1,118 @@
+// Intermediate representation of a laid-out statusline.
+//
+// `StatusLineGenerator` turns segments into lines of styled spans: segment
+// text, separators, caps and alignment padding, with colors already reduced to
+// the output color depth. Every output is produced from these lines:
+//
+//   terminal, tmux, zsh, bash   targets::render_line
+//   TUI preview                 layout::to_tui_line
+//
+// so the preview always shows exactly what the statusline prints.
+
+use crate::config::{AnsiColor, Underline};
+use crate::core::width::visible_width;
+
+/// Visual attributes of a span. `None` colors use the terminal default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub fg: Option<AnsiColor>,
+    pub bg: Option<AnsiColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: Underline,
+    pub underline_color: Option<AnsiColor>,
+    pub strikethrough: bool,
+    pub inverse: bool,
+}
+
+/// A run of text drawn with one style, optionally a hyperlink.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+    pub link: Option<String>,
+}
+
+/// One output line.
+pub type Line = Vec<Span>;
+
+impl Span {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::styled(text, Style::default())
+    }
+
+    pub fn styled(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            link: None,
+        }
+    }
+}
+
+/// Append `span` to `line`, skipping empty text and merging it into the last
+/// span when both look the same.
+pub fn push_span(line: &mut Line, span: Span) {
+    if span.text.is_empty() {
+        return;
+    }
+    match line.last_mut() {
+        Some(last) if last.style == span.style && last.link == span.link => {
+            last.text.push_str(&span.text)
+        }
+        _ => line.push(span),
+    }
+}
+
+/// Terminal columns taken by a line.
+pub fn line_width(line: &[Span]) -> usize {
+    line.iter().map(|span| visible_width(&span.text)).sum()
+}
+
+/// Convert a line to ratatui spans. Hyperlinks are not shown in the TUI, and
+/// every underline shape is drawn as a plain underline.
+#[cfg(feature = "tui")]
+pub fn to_tui_line(line: &[Span]) -> ratatui::text::Line<'static> {
+    use ratatui::style::{Color, Modifier};
+
+    let color = |color: &AnsiColor| match color {
+        AnsiColor::Color16 { c16 } => Color::Indexed(*c16),
+        AnsiColor::Color256 { c256 } => Color::Indexed(*c256),
+        AnsiColor::Rgb { r, g, b } => Color::Rgb(*r, *g, *b),
+    };
+
+    let spans: Vec<ratatui::text::Span<'static>> = line
+        .iter()
+        .map(|span| {
+            let mut style = ratatui::style::Style::default();
+            if let Some(fg) = &span.style.fg {
+                style = style.fg(color(fg));
+            }
+            if let Some(bg) = &span.style.bg {
+                style = style.bg(color(bg));
+            }
+            if let Some(underline) = &span.style.underline_color {
+                style = style.underline_color(color(underline));
+            }
+            let modifiers = [
+                (span.style.bold, Modifier::BOLD),
+                (span.style.dim, Modifier::DIM),
+                (span.style.italic, Modifier::ITALIC),
+                (
+                    span.style.underline != Underline::None,
+                    Modifier::UNDERLINED,
+                ),
+                (span.style.strikethrough, Modifier::CROSSED_OUT),
+                (span.style.inverse, Modifier::REVERSED),
+            ];
+            for (enabled, modifier) in modifiers {
+                if enabled {
+                    style = style.add_modifier(modifier);
+                }
+            }
+            ratatui::text::Span::styled(span.text.clone(), style)
+        })
+        .collect();
+    ratatui::text::Line::from(spans)
+}
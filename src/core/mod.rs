@@ -1,4 +1,14 @@
+pub mod color;
+pub mod format;
+pub mod hyperlink;
+pub mod json;
+pub mod layout;
 pub mod segments;
 pub mod statusline;
+pub mod targets;
+pub mod thresholds;
+pub mod transcript;
+pub mod uuid_index;
+pub mod width;
 
 pub use statusline::{collect_all_segments, StatusLineGenerator};
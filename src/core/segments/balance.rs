@@ -1,6 +1,7 @@
 use super::{Segment, SegmentData};
 use crate::api::cache;
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -13,12 +14,14 @@ impl BalanceSegment {
 }
 
 impl Segment for BalanceSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let data = cache::fetch_balance()?;
 
         let mut metadata = HashMap::new();
         metadata.insert("balance".to_string(), data.balance.to_string());
         metadata.insert("is_unlimited".to_string(), data.is_unlimited.to_string());
+        metadata.insert("balance_display".to_string(), data.format_balance());
+        metadata.insert("compact".to_string(), data.format_balance());
         if let Some(ref g) = data.group_name {
             metadata.insert("group".to_string(), g.clone());
         }
@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -19,7 +20,7 @@ impl BrandingSegment {
 }
 
 impl Segment for BrandingSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         Some(SegmentData {
             primary: self.text.clone(),
             secondary: String::new(),
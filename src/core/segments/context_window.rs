@@ -1,8 +1,8 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, ModelConfig, SegmentId, TranscriptEntry};
+use crate::config::{InputData, ModelConfig, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
 #[derive(Default)]
@@ -21,11 +21,11 @@ impl ContextWindowSegment {
 }
 
 impl Segment for ContextWindowSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, transcript: &Transcript) -> Option<SegmentData> {
         // Dynamically determine context limit based on current model ID
         let context_limit = Self::get_context_limit_for_model(&input.model.id);
 
-        let context_used_token_opt = parse_transcript_usage(&input.transcript_path);
+        let context_used_token_opt = context_usage(transcript);
 
         let (percentage_display, tokens_display) = match context_used_token_opt {
             Some(context_used_token) => {
@@ -62,6 +62,12 @@ impl Segment for ContextWindowSegment {
                 let context_used_rate = (context_used_token as f64 / context_limit as f64) * 100.0;
                 metadata.insert("tokens".to_string(), context_used_token.to_string());
                 metadata.insert("percentage".to_string(), context_used_rate.to_string());
+                metadata.insert(
+                    "percentage_display".to_string(),
+                    percentage_display.trim_end_matches('%').to_string(),
+                );
+                metadata.insert("tokens_k".to_string(), tokens_display.clone());
+                metadata.insert("compact".to_string(), percentage_display.clone());
             }
             None => {
                 metadata.insert("tokens".to_string(), "-".to_string());
@@ -69,7 +75,30 @@ impl Segment for ContextWindowSegment {
             }
         }
         metadata.insert("limit".to_string(), context_limit.to_string());
+        metadata.insert("limit_k".to_string(), format!("{}k", context_limit / 1000));
         metadata.insert("model".to_string(), input.model.id.clone());
+        // Subagents have their own context; their usage is reported apart
+        if let Some(tail) = transcript.tail() {
+            metadata.insert(
+                "subagent_tokens".to_string(),
+                tail.subagent_tokens.to_string(),
+            );
+            metadata.insert(
+                "subagent_turns".to_string(),
+                tail.subagent_turns.to_string(),
+            );
+            metadata.insert("compactions".to_string(), tail.compactions.to_string());
+            if let Some(reclaimed) = tail
+                .last_compaction
+                .as_ref()
+                .and_then(|compaction| compaction.reclaimed_tokens())
+            {
+                metadata.insert("reclaimed_tokens".to_string(), reclaimed.to_string());
+            }
+            // `tokens` is the estimated size right after a compaction
+            let estimated = tail.summary_leaf.is_none() && tail.context_estimated();
+            metadata.insert("tokens_estimated".to_string(), estimated.to_string());
+        }
 
         Some(SegmentData {
             primary: format!("{} · {} tokens", percentage_display, tokens_display),
@@ -83,17 +112,15 @@ impl Segment for ContextWindowSegment {
     }
 }
 
-fn parse_transcript_usage<P: AsRef<Path>>(transcript_path: P) -> Option<u32> {
-    let path = transcript_path.as_ref();
-
+fn context_usage(transcript: &Transcript) -> Option<u32> {
     // Try to parse from current transcript file
-    if let Some(usage) = try_parse_transcript_file(path) {
+    if let Some(usage) = usage_from(transcript) {
         return Some(usage);
     }
 
     // If file doesn't exist, try to find usage from project history
-    if !path.exists() {
-        if let Some(usage) = try_find_usage_from_project_history(path) {
+    if !transcript.path().exists() {
+        if let Some(usage) = try_find_usage_from_project_history(transcript.path()) {
             return Some(usage);
         }
     }
@@ -101,136 +128,15 @@ fn parse_transcript_usage<P: AsRef<Path>>(transcript_path: P) -> Option<u32> {
     None
 }
 
-fn try_parse_transcript_file(path: &Path) -> Option<u32> {
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap_or_default();
-
-    if lines.is_empty() {
-        return None;
-    }
-
-    // Check if the last line is a summary
-    let last_line = lines.last()?.trim();
-    if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(last_line) {
-        if entry.r#type.as_deref() == Some("summary") {
-            // Handle summary case: find usage by leafUuid
-            if let Some(leaf_uuid) = &entry.leaf_uuid {
-                let project_dir = path.parent()?;
-                return find_usage_by_leaf_uuid(leaf_uuid, project_dir);
-            }
-        }
+fn usage_from(transcript: &Transcript) -> Option<u32> {
+    let tail = transcript.tail()?;
+    // A transcript ending in a summary continues another session
+    if tail.summary_leaf.is_some() {
+        return tail.summary_usage;
     }
 
-    // Normal case: find the last assistant message in current file
-    for line in lines.iter().rev() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
-            if entry.r#type.as_deref() == Some("assistant") {
-                if let Some(message) = &entry.message {
-                    if let Some(raw_usage) = &message.usage {
-                        let normalized = raw_usage.clone().normalize();
-                        return Some(normalized.display_tokens());
-                    }
-                }
-            }
-        }
-    }
-
-    None
-}
-
-fn find_usage_by_leaf_uuid(leaf_uuid: &str, project_dir: &Path) -> Option<u32> {
-    // Search for the leafUuid across all session files in the project directory
-    let entries = fs::read_dir(project_dir).ok()?;
-
-    for entry in entries {
-        let entry = entry.ok()?;
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
-            continue;
-        }
-
-        if let Some(usage) = search_uuid_in_file(&path, leaf_uuid) {
-            return Some(usage);
-        }
-    }
-
-    None
-}
-
-fn search_uuid_in_file(path: &Path, target_uuid: &str) -> Option<u32> {
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap_or_default();
-
-    // Find the message with target_uuid
-    for line in &lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
-            if let Some(uuid) = &entry.uuid {
-                if uuid == target_uuid {
-                    // Found the target message, check its type
-                    if entry.r#type.as_deref() == Some("assistant") {
-                        // Direct assistant message with usage
-                        if let Some(message) = &entry.message {
-                            if let Some(raw_usage) = &message.usage {
-                                let normalized = raw_usage.clone().normalize();
-                                return Some(normalized.display_tokens());
-                            }
-                        }
-                    } else if entry.r#type.as_deref() == Some("user") {
-                        // User message, need to find the parent assistant message
-                        if let Some(parent_uuid) = &entry.parent_uuid {
-                            return find_assistant_message_by_uuid(&lines, parent_uuid);
-                        }
-                    }
-                    break;
-                }
-            }
-        }
-    }
-
-    None
-}
-
-fn find_assistant_message_by_uuid(lines: &[String], target_uuid: &str) -> Option<u32> {
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
-            if let Some(uuid) = &entry.uuid {
-                if uuid == target_uuid && entry.r#type.as_deref() == Some("assistant") {
-                    if let Some(message) = &entry.message {
-                        if let Some(raw_usage) = &message.usage {
-                            let normalized = raw_usage.clone().normalize();
-                            return Some(normalized.display_tokens());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    None
+    // Right after a compaction this is the compacted size, not the last usage
+    tail.context_tokens()
 }
 
 fn try_find_usage_from_project_history(transcript_path: &Path) -> Option<u32> {
@@ -263,7 +169,7 @@ fn try_find_usage_from_project_history(transcript_path: &Path) -> Option<u32> {
 
     // Try to find usage from the most recent session
     for session_path in &session_files {
-        if let Some(usage) = try_parse_transcript_file(session_path) {
+        if let Some(usage) = usage_from(&Transcript::new(session_path)) {
             return Some(usage);
         }
     }
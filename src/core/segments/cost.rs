@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -12,7 +13,7 @@ impl CostSegment {
 }
 
 impl Segment for CostSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let cost_data = input.cost.as_ref()?;
 
         // Primary display: total cost
@@ -32,6 +33,7 @@ impl Segment for CostSegment {
         let mut metadata = HashMap::new();
         if let Some(cost) = cost_data.total_cost_usd {
             metadata.insert("cost".to_string(), cost.to_string());
+            metadata.insert("cost_display".to_string(), primary.clone());
         }
 
         Some(SegmentData {
@@ -1,5 +1,7 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::hyperlink::file_url;
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 const MAX_SEGMENTS: usize = 5;
@@ -75,11 +77,16 @@ impl CwdSegment {
 }
 
 impl Segment for CwdSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let display = Self::format_path(&input.workspace.current_dir);
 
         let mut metadata = HashMap::new();
         metadata.insert("full_path".to_string(), input.workspace.current_dir.clone());
+        metadata.insert("path".to_string(), display.clone());
+        metadata.insert("link".to_string(), file_url(&input.workspace.current_dir));
+        if let Some(name) = display.rsplit(['/', '\\']).find(|part| !part.is_empty()) {
+            metadata.insert("compact".to_string(), name.to_string());
+        }
 
         Some(SegmentData {
             primary: display,
@@ -1,5 +1,7 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::hyperlink::file_url;
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -37,7 +39,7 @@ impl DirectorySegment {
 }
 
 impl Segment for DirectorySegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let current_dir = &input.workspace.current_dir;
 
         // Handle cross-platform path separators manually for better compatibility
@@ -46,6 +48,8 @@ impl Segment for DirectorySegment {
         // Store the full path in metadata for potential use
         let mut metadata = HashMap::new();
         metadata.insert("full_path".to_string(), current_dir.clone());
+        metadata.insert("dir_name".to_string(), dir_name.clone());
+        metadata.insert("link".to_string(), file_url(current_dir));
 
         Some(SegmentData {
             primary: dir_name,
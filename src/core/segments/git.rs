@@ -1,5 +1,7 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::hyperlink::remote_web_url;
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 use std::process::Command;
 
@@ -151,6 +153,21 @@ impl GitSegment {
         }
     }
 
+    fn get_remote_url(&self, working_dir: &str) -> Option<String> {
+        let output = Command::new("git")
+            .args(["config", "--get", "remote.origin.url"])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            let url = String::from_utf8(output.stdout).ok()?.trim().to_string();
+            (!url.is_empty()).then_some(url)
+        } else {
+            None
+        }
+    }
+
     fn get_sha(&self, working_dir: &str) -> Option<String> {
         let output = Command::new("git")
             .args(["--no-optional-locks", "rev-parse", "--short=7", "HEAD"])
@@ -172,11 +189,12 @@ impl GitSegment {
 }
 
 impl Segment for GitSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let git_info = self.get_git_info(&input.workspace.current_dir)?;
 
         let mut metadata = HashMap::new();
         metadata.insert("branch".to_string(), git_info.branch.clone());
+        metadata.insert("compact".to_string(), git_info.branch.clone());
         metadata.insert("status".to_string(), format!("{:?}", git_info.status));
         metadata.insert("ahead".to_string(), git_info.ahead.to_string());
         metadata.insert("behind".to_string(), git_info.behind.to_string());
@@ -185,14 +203,24 @@ impl Segment for GitSegment {
             metadata.insert("sha".to_string(), sha.clone());
         }
 
+        let branch = (git_info.branch != "detached").then_some(git_info.branch.as_str());
+        if let Some(link) = self
+            .get_remote_url(&input.workspace.current_dir)
+            .and_then(|remote| remote_web_url(&remote, branch))
+        {
+            metadata.insert("link".to_string(), link);
+        }
+
         let primary = git_info.branch;
         let mut status_parts = Vec::new();
 
-        match git_info.status {
-            GitStatus::Clean => status_parts.push("✓".to_string()),
-            GitStatus::Dirty => status_parts.push("●".to_string()),
-            GitStatus::Conflicts => status_parts.push("⚠".to_string()),
-        }
+        let status_icon = match git_info.status {
+            GitStatus::Clean => "✓",
+            GitStatus::Dirty => "●",
+            GitStatus::Conflicts => "⚠",
+        };
+        metadata.insert("status_icon".to_string(), status_icon.to_string());
+        status_parts.push(status_icon.to_string());
 
         if git_info.ahead > 0 {
             status_parts.push(format!("↑{}", git_info.ahead));
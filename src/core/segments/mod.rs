@@ -13,11 +13,13 @@ pub mod usage;
 pub mod used;
 
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
-// New Segment trait for data collection only
+// New Segment trait for data collection only. The transcript is shared by all
+// segments of one invocation and parsed at most once.
 pub trait Segment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData>;
+    fn collect(&self, input: &InputData, transcript: &Transcript) -> Option<SegmentData>;
     fn id(&self) -> SegmentId;
 }
 
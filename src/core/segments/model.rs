@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -12,7 +13,7 @@ impl ModelSegment {
 }
 
 impl Segment for ModelSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let mut metadata = HashMap::new();
         metadata.insert("model_id".to_string(), input.model.id.clone());
         metadata.insert("display_name".to_string(), input.model.display_name.clone());
@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -12,7 +13,7 @@ impl OutputStyleSegment {
 }
 
 impl Segment for OutputStyleSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let output_style = input.output_style.as_ref()?;
 
         // Primary display: style name
@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -37,7 +38,7 @@ impl SessionSegment {
 }
 
 impl Segment for SessionSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let cost_data = input.cost.as_ref()?;
 
         // Primary display: total duration
@@ -64,6 +65,8 @@ impl Segment for SessionSegment {
         let mut metadata = HashMap::new();
         if let Some(duration) = cost_data.total_duration_ms {
             metadata.insert("duration_ms".to_string(), duration.to_string());
+            metadata.insert("duration".to_string(), primary.clone());
+            metadata.insert("compact".to_string(), primary.clone());
         }
         if let Some(api_duration) = cost_data.total_api_duration_ms {
             metadata.insert("api_duration_ms".to_string(), api_duration.to_string());
@@ -74,6 +77,9 @@ impl Segment for SessionSegment {
         if let Some(removed) = cost_data.total_lines_removed {
             metadata.insert("lines_removed".to_string(), removed.to_string());
         }
+        if !secondary.is_empty() {
+            metadata.insert("lines".to_string(), secondary.clone());
+        }
 
         Some(SegmentData {
             primary,
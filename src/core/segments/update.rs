@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use crate::updater::UpdateState;
 
 #[derive(Default)]
@@ -12,14 +13,20 @@ impl UpdateSegment {
 }
 
 impl Segment for UpdateSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         // Load update state and check for update status
         let update_state = UpdateState::load();
 
         update_state.status_text().map(|status_text| SegmentData {
+            metadata: std::collections::HashMap::from([
+                ("status".to_string(), status_text.clone()),
+                (
+                    "link".to_string(),
+                    format!("{}/releases/latest", env!("CARGO_PKG_REPOSITORY")),
+                ),
+            ]),
             primary: status_text,
             secondary: String::new(),
-            metadata: std::collections::HashMap::new(),
         })
     }
 
@@ -1,5 +1,6 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use crate::utils::credentials;
 use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
 use serde::{Deserialize, Serialize};
@@ -181,7 +182,7 @@ impl UsageSegment {
 }
 
 impl Segment for UsageSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let token = credentials::get_oauth_token()?;
 
         // Load config from file to get segment options
@@ -261,6 +262,16 @@ impl Segment for UsageSegment {
             "seven_day_utilization".to_string(),
             seven_day_util.to_string(),
         );
+        metadata.insert("five_hour".to_string(), five_hour_percent.to_string());
+        metadata.insert("compact".to_string(), primary.clone());
+        metadata.insert(
+            "seven_day".to_string(),
+            (seven_day_util.round() as u8).to_string(),
+        );
+        metadata.insert(
+            "resets".to_string(),
+            Self::format_reset_time(resets_at.as_deref()),
+        );
 
         Some(SegmentData {
             primary,
@@ -1,6 +1,7 @@
 use super::{Segment, SegmentData};
 use crate::api::cache;
 use crate::config::{InputData, SegmentId};
+use crate::core::transcript::Transcript;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -13,11 +14,13 @@ impl UsedSegment {
 }
 
 impl Segment for UsedSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData, _transcript: &Transcript) -> Option<SegmentData> {
         let data = cache::fetch_balance()?;
 
         let mut metadata = HashMap::new();
         metadata.insert("used".to_string(), data.used.to_string());
+        metadata.insert("used_display".to_string(), data.format_used());
+        metadata.insert("compact".to_string(), data.format_used());
         if let Some(ref g) = data.group_name {
             metadata.insert("group".to_string(), g.clone());
         }
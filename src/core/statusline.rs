@@ -1,79 +1,200 @@
-use crate::config::{AnsiColor, Config, SegmentConfig, StyleMode};
+use crate::config::{Align, AnsiColor, ColorDepth, Config, SegmentConfig, SegmentId, StyleMode};
+use crate::core::color::{detect_depth, downsample, gradient};
+use crate::core::format::render_template;
+#[cfg(feature = "tui")]
+use crate::core::layout::to_tui_line;
+use crate::core::layout::{line_width, push_span, Line, Span, Style};
 use crate::core::segments::SegmentData;
-use unicode_width::UnicodeWidthChar;
-
-/// Strip ANSI escape sequences and return visible display width
-/// Correctly handles CJK wide characters (2 columns per character)
-fn visible_width(text: &str) -> usize {
-    let mut width = 0;
-    let mut in_escape = false;
-    let mut chars = text.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        if ch == '\x1b' {
-            // Start of ANSI escape sequence
-            in_escape = true;
-            // Skip the [ character
-            if chars.peek() == Some(&'[') {
-                chars.next();
-            }
-        } else if in_escape {
-            // Skip until we find the end of the escape sequence (letter)
-            if ch.is_alphabetic() {
-                in_escape = false;
-            }
-        } else {
-            // Use unicode width for correct CJK character handling
-            width += ch.width().unwrap_or(0);
-        }
-    }
+use crate::core::targets::{render_line, RenderTarget};
+use crate::core::thresholds::{apply_thresholds, rule_matches};
+use crate::core::transcript::Transcript;
+
+/// Collected segments of one statusline row, in display order.
+type Row = Vec<(SegmentConfig, SegmentData)>;
+
+/// Separators and caps next to segments without a background.
+const WHITE: AnsiColor = AnsiColor::Color16 { c16: 7 };
+
+/// How a separator is colored between two segments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SeparatorKind {
+    /// Solid powerline glyph: previous background on the next background.
+    Filled,
+    /// Outline powerline glyph, drawn like `Filled` unless both sides share a
+    /// background.
+    Thin,
+    /// Any other text, drawn in white.
+    Text,
+}
 
-    width
+fn separator_kind(separator: &str) -> SeparatorKind {
+    match separator {
+        // Arrow, rounded, lower/upper slant, flame
+        "\u{e0b0}" | "\u{e0b4}" | "\u{e0b8}" | "\u{e0bc}" | "\u{e0c0}" => SeparatorKind::Filled,
+        "\u{e0b1}" | "\u{e0b5}" | "\u{e0b9}" | "\u{e0bd}" | "\u{e0c1}" => SeparatorKind::Thin,
+        _ => SeparatorKind::Text,
+    }
 }
 
-/// Segments that should always start on a new line so the statusline reflows into
-/// multi-row form (row 1 = upstream metrics, row 2 = path/git, row 3 = account + branding).
-fn forces_newline(id: crate::config::SegmentId) -> bool {
-    matches!(
-        id,
-        crate::config::SegmentId::Cwd | crate::config::SegmentId::Used
-    )
+/// Filled opening and closing glyphs matching a powerline separator, used to
+/// cap each line when no caps are configured.
+fn edge_glyphs(separator: &str) -> Option<(&'static str, &'static str)> {
+    match separator {
+        "\u{e0b0}" | "\u{e0b1}" => Some(("\u{e0b2}", "\u{e0b0}")),
+        "\u{e0b4}" | "\u{e0b5}" => Some(("\u{e0b6}", "\u{e0b4}")),
+        "\u{e0b8}" | "\u{e0b9}" => Some(("\u{e0ba}", "\u{e0b8}")),
+        "\u{e0bc}" | "\u{e0bd}" => Some(("\u{e0be}", "\u{e0bc}")),
+        "\u{e0c0}" | "\u{e0c1}" => Some(("\u{e0c2}", "\u{e0c0}")),
+        _ => None,
+    }
 }
 
 pub struct StatusLineGenerator {
     config: Config,
+    depth: ColorDepth,
+    target: RenderTarget,
 }
 
 impl StatusLineGenerator {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let depth = detect_depth(config.style.color_depth);
+        Self {
+            config,
+            depth,
+            target: RenderTarget::Terminal,
+        }
     }
 
+    /// Emit tmux or shell prompt markup instead of raw ANSI escapes.
+    pub fn with_target(mut self, target: RenderTarget) -> Self {
+        self.target = target;
+        // tmux adapts colors to each attached client itself, so the terminal
+        // tmux was started from says nothing about what can be shown
+        if target == RenderTarget::Tmux
+            && self.config.style.color_depth == ColorDepth::Auto
+            && self.depth != ColorDepth::None
+        {
+            self.depth = ColorDepth::Truecolor;
+        }
+        self
+    }
+
+    /// Render every row on its own line, without width-based wrapping. For
+    /// tmux, whose `#()` shows only the first line of output, the rows are
+    /// joined into one line instead.
     pub fn generate(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
-        let mut output = Vec::new();
-        let enabled_segments: Vec<_> = segments
+        let lines: Vec<String> = self
+            .layout(segments, None)
+            .iter()
+            .map(|line| render_line(line, self.target))
+            .collect();
+        match self.target {
+            RenderTarget::Tmux => lines.join(&self.row_joiner()),
+            _ => lines.join("\n"),
+        }
+    }
+
+    /// Text between rows flattened onto one line: the separator when it is
+    /// plain text, a space for powerline glyphs, which need a background on
+    /// both sides.
+    fn row_joiner(&self) -> String {
+        let separator = &self.config.style.separator;
+        let text = match separator_kind(separator) {
+            SeparatorKind::Text => separator.as_str(),
+            SeparatorKind::Filled | SeparatorKind::Thin => " ",
+        };
+        render_line(&[Span::plain(text)], self.target)
+    }
+
+    /// Lay out the statusline as lines of styled spans, the representation
+    /// every output is rendered from.
+    ///
+    /// With a `max_width`, rows are fitted, wrapped and aligned to it; without
+    /// one, each row becomes a single line in segment order.
+    pub fn layout(
+        &self,
+        segments: Vec<(SegmentConfig, SegmentData)>,
+        max_width: Option<usize>,
+    ) -> Vec<Line> {
+        let rows = self.split_rows(segments).into_iter();
+        match max_width {
+            Some(max_width) => rows
+                .map(|row| self.apply_gradient(self.fit_row(row, max_width)))
+                .flat_map(|row| self.align_row(row, max_width))
+                .collect(),
+            None => rows
+                .map(|row| self.apply_gradient(row))
+                .map(|row| self.join_line(&self.render_row(&row)))
+                .filter(|line| !line.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Group enabled segments into the configured rows, in row order.
+    ///
+    /// Enabled segments missing from `rows` are appended to the last row, so a
+    /// newly enabled segment never silently disappears. Empty rows are dropped.
+    fn split_rows(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> Vec<Row> {
+        let mut remaining: Vec<_> = segments
             .into_iter()
             .filter(|(config, _)| config.enabled)
+            .map(|(config, data)| (apply_thresholds(config, &data), data))
             .collect();
 
-        for (config, data) in enabled_segments.iter() {
-            let rendered = self.render_segment(config, data);
-            if !rendered.is_empty() {
-                output.push(rendered);
+        let mut rows: Vec<Row> = Vec::new();
+        for ids in &self.config.rows {
+            let mut row = Vec::new();
+            for id in ids {
+                if let Some(pos) = remaining.iter().position(|(config, _)| config.id == *id) {
+                    row.push(remaining.remove(pos));
+                }
             }
+            rows.push(row);
         }
 
-        if output.is_empty() {
-            return String::new();
+        match rows.iter_mut().rev().find(|row| !row.is_empty()) {
+            Some(last) => last.extend(remaining),
+            None => rows = vec![remaining],
         }
 
-        // Handle Powerline arrow separators with color transition
-        if self.config.style.separator == "\u{e0b0}" {
-            self.join_with_powerline_arrows(&output, &enabled_segments)
-        } else {
-            // For all other separators, use white color and simple join
-            self.join_with_white_separators(&output, &enabled_segments)
+        rows.retain(|row| !row.is_empty());
+        rows
+    }
+
+    /// Give the visible segments of a row the theme gradient as background.
+    ///
+    /// Applied to the fitted row, so the gradient always spans the segments
+    /// actually shown; `row_width` applies it too while fitting, since a
+    /// background brings padding and caps. Backgrounds from a matching
+    /// threshold rule are kept, so warnings still stand out.
+    fn apply_gradient(&self, mut row: Row) -> Row {
+        let stops = &self.config.style.gradient;
+        if stops.is_empty() {
+            return row;
         }
+
+        let visible: Vec<usize> = (0..row.len())
+            .filter(|&i| !self.render_segment(&row[i].0, &row[i].1).is_empty())
+            .collect();
+        let colors = gradient(stops, visible.len());
+        for (i, color) in visible.into_iter().zip(colors) {
+            let (config, data) = &mut row[i];
+            let warned = config.thresholds.iter().any(|rule| {
+                rule.colors.background.is_some() && rule_matches(config.id, rule, data)
+            });
+            if !warned {
+                config.colors.background = Some(color);
+            }
+        }
+        row
+    }
+
+    /// Render each segment of a row, dropping segments with no output.
+    fn render_row<'a>(&self, row: &'a Row) -> Vec<(&'a SegmentConfig, Line)> {
+        row.iter()
+            .map(|(config, data)| (config, self.render_segment(config, data)))
+            .filter(|(_, rendered)| !rendered.is_empty())
+            .collect()
     }
 
     /// Generate statusline with intelligent wrapping based on terminal width.
@@ -83,355 +204,315 @@ impl StatusLineGenerator {
         segments: Vec<(SegmentConfig, SegmentData)>,
         max_width: usize,
     ) -> Vec<String> {
-        let mut rendered_segments = Vec::new();
-        let enabled_segments: Vec<_> = segments
+        let lines: Vec<String> = self
+            .layout(segments, Some(max_width))
+            .iter()
+            .map(|line| render_line(line, self.target))
+            .collect();
+
+        if lines.is_empty() {
+            vec![String::new()]
+        } else {
+            lines
+        }
+    }
+
+    /// Shrink a row that does not fit in `max_width` before it is wrapped.
+    ///
+    /// Segments whose `min_width` exceeds the width are hidden outright. Then,
+    /// lowest priority first, segments switch to their compact form, and if the
+    /// row still overflows, segments that have a priority are hidden.
+    fn fit_row(&self, row: Row, max_width: usize) -> Row {
+        let mut row: Row = row
             .into_iter()
-            .filter(|(config, _)| config.enabled)
+            .filter(|(config, _)| match config.min_width {
+                Some(min) => max_width >= min,
+                None => true,
+            })
             .collect();
+        if self.row_width(&row) <= max_width {
+            return row;
+        }
 
-        for (config, data) in enabled_segments.iter() {
-            let rendered = self.render_segment(config, data);
-            if !rendered.is_empty() {
-                rendered_segments.push(rendered);
+        // Unprioritized segments are compacted last
+        let mut order: Vec<usize> = (0..row.len()).collect();
+        order.sort_by_key(|&i| row[i].0.priority.map_or(u16::MAX, u16::from));
+        for &i in &order {
+            if let Some(compact) = Self::compact(&row[i]) {
+                row[i] = compact;
+                if self.row_width(&row) <= max_width {
+                    return row;
+                }
             }
         }
 
-        if rendered_segments.is_empty() {
-            return vec![String::new()];
+        let mut droppable: Vec<(u8, SegmentId)> = row
+            .iter()
+            .filter_map(|(config, _)| config.priority.map(|p| (p, config.id)))
+            .collect();
+        droppable.sort_by_key(|(priority, _)| *priority);
+        for (_, id) in droppable {
+            row.retain(|(config, _)| config.id != id);
+            if self.row_width(&row) <= max_width {
+                break;
+            }
         }
 
-        // Pre-calculate separators between segments
-        let mut separators = Vec::new();
-        for i in 0..rendered_segments.len().saturating_sub(1) {
-            let separator = if self.config.style.separator == "\u{e0b0}" {
-                let prev_bg = enabled_segments
-                    .get(i)
-                    .and_then(|(config, _)| config.colors.background.as_ref());
-                let curr_bg = enabled_segments
-                    .get(i + 1)
-                    .and_then(|(config, _)| config.colors.background.as_ref());
-                self.create_powerline_arrow(prev_bg, curr_bg)
-            } else {
-                format!("\x1b[37m{}\x1b[0m", self.config.style.separator)
-            };
-            separators.push(separator);
-        }
+        row
+    }
 
-        // Check if single line fits
-        let full_line = if self.config.style.separator == "\u{e0b0}" {
-            self.join_with_powerline_arrows(&rendered_segments, &enabled_segments)
-        } else {
-            self.join_with_white_separators(&rendered_segments, &enabled_segments)
+    /// Compact form of a segment: the `compact_format` option rendered as a
+    /// template, or else the segment's own `compact` metadata.
+    fn compact(segment: &(SegmentConfig, SegmentData)) -> Option<(SegmentConfig, SegmentData)> {
+        let (config, data) = segment;
+        let text = match config
+            .options
+            .get("compact_format")
+            .and_then(|v| v.as_str())
+        {
+            Some(template) => render_template(template, data)?,
+            None => data.metadata.get("compact")?.clone(),
         };
 
-        if visible_width(&full_line) <= max_width {
-            return vec![full_line];
+        let mut config = config.clone();
+        config.options.remove("format");
+        let data = SegmentData {
+            primary: text,
+            secondary: String::new(),
+            metadata: data.metadata.clone(),
+        };
+        Some((config, data))
+    }
+
+    /// Width of a row rendered on a single line, caps included, with the
+    /// backgrounds the gradient will give it.
+    fn row_width(&self, row: &Row) -> usize {
+        if self.config.style.gradient.is_empty() {
+            return line_width(&self.join_line(&self.render_row(row)));
         }
+        let row = self.apply_gradient(row.clone());
+        line_width(&self.join_line(&self.render_row(&row)))
+    }
 
-        // Intelligent line wrapping by segment
-        let mut lines: Vec<String> = Vec::new();
-        let mut current_line = String::new();
-        let mut current_width = 0usize;
+    /// Lay out one row as left, center and right groups across `max_width`.
+    ///
+    /// When all groups fit on one line they share it, separated by padding.
+    /// Otherwise each group is wrapped on its own; center lines are centered,
+    /// right lines flush right, and the first right-aligned line is pulled up
+    /// next to the previous group's last line if there is room.
+    fn align_row(&self, row: Row, max_width: usize) -> Vec<Line> {
+        let (mut left, mut center, mut right) = (Vec::new(), Vec::new(), Vec::new());
+        for segment in row {
+            match segment.0.align {
+                Align::Left => left.push(segment),
+                Align::Center => center.push(segment),
+                Align::Right => right.push(segment),
+            }
+        }
+        if center.is_empty() && right.is_empty() {
+            return self.break_row(&left, max_width);
+        }
 
-        for i in 0..rendered_segments.len() {
-            let segment = &rendered_segments[i];
-            let segment_width = visible_width(segment);
+        let left = self.break_row(&left, max_width);
+        let center = self.break_row(&center, max_width);
+        let right = self.break_row(&right, max_width);
+
+        // Everything on one line
+        if left.len() <= 1 && center.len() <= 1 && right.len() <= 1 {
+            let l = left.into_iter().next().unwrap_or_default();
+            let c = center.into_iter().next().unwrap_or_default();
+            let r = right.into_iter().next().unwrap_or_default();
+            let (lw, cw, rw) = (line_width(&l), line_width(&c), line_width(&r));
+            let gap = |w: usize| usize::from(w > 0);
+            let needed = lw + gap(lw) + cw + gap(cw) + rw;
+            if needed <= max_width {
+                let mut line = l;
+                let mut used = lw;
+                if cw > 0 {
+                    let latest = max_width - rw - gap(rw) - cw;
+                    let start = ((max_width - cw) / 2).clamp(lw + gap(lw), latest);
+                    line.push(Self::pad(start - used));
+                    line.extend(c);
+                    used = start + cw;
+                }
+                if rw > 0 {
+                    line.push(Self::pad(max_width - rw - used));
+                    line.extend(r);
+                }
+                return vec![line];
+            }
+            return Self::stack(vec![l], vec![c], vec![r], max_width);
+        }
 
-            // Force a new line in front of any forced-break segment so the layout splits into rows.
-            let force_break = i > 0
-                && enabled_segments
-                    .get(i)
-                    .map(|(c, _)| forces_newline(c.id))
-                    .unwrap_or(false);
+        Self::stack(left, center, right, max_width)
+    }
 
-            if force_break && !current_line.is_empty() {
-                current_line.push_str("\x1b[0m");
-                lines.push(current_line.clone());
-                current_line.clear();
-                current_width = 0;
-            }
-
-            // Check if adding this segment would exceed max_width
-            if current_width > 0 && current_width + segment_width > max_width {
-                // Add reset before line break to prevent color bleeding
-                current_line.push_str("\x1b[0m");
-                lines.push(current_line.clone());
-                current_line.clear();
-                current_width = 0;
-            }
-
-            // Add the segment to current line
-            current_line.push_str(segment);
-            current_width += segment_width;
-
-            // Handle separator if not the last segment
-            if i < separators.len() {
-                let separator = &separators[i];
-                let separator_width = visible_width(separator);
-
-                if i + 1 < rendered_segments.len() {
-                    let next_segment = &rendered_segments[i + 1];
-                    let next_width = visible_width(next_segment);
-
-                    if current_width + separator_width + next_width <= max_width {
-                        current_line.push_str(separator);
-                        current_width += separator_width;
-                    } else {
-                        // Add reset before line break
-                        current_line.push_str("\x1b[0m");
-                        lines.push(current_line.clone());
-                        current_line.clear();
-                        current_width = 0;
+    /// Groups that do not share a line: left lines as they are, then centered
+    /// and flush-right lines.
+    fn stack(left: Vec<Line>, center: Vec<Line>, right: Vec<Line>, max_width: usize) -> Vec<Line> {
+        let mut lines: Vec<Line> = left.into_iter().filter(|l| !l.is_empty()).collect();
+        for line in center.into_iter().filter(|l| !l.is_empty()) {
+            let width = line_width(&line);
+            let mut padded = vec![Self::pad(max_width.saturating_sub(width) / 2)];
+            padded.extend(line);
+            lines.push(padded);
+        }
+        for (i, line) in right.into_iter().filter(|l| !l.is_empty()).enumerate() {
+            let width = line_width(&line);
+            if i == 0 {
+                if let Some(last) = lines.last_mut() {
+                    let last_width = line_width(last);
+                    if last_width + 1 + width <= max_width {
+                        last.push(Self::pad(max_width - width - last_width));
+                        last.extend(line);
+                        continue;
                     }
                 }
             }
+            let mut padded = vec![Self::pad(max_width.saturating_sub(width))];
+            padded.extend(line);
+            lines.push(padded);
         }
+        lines
+    }
+
+    /// Unstyled spaces, so padding never picks up a background color.
+    fn pad(width: usize) -> Span {
+        Span::plain(" ".repeat(width))
+    }
 
-        // Add the last line
-        if !current_line.is_empty() {
-            current_line.push_str("\x1b[0m");
-            lines.push(current_line);
+    /// Split a row into lines of at most `max_width` columns (unless a single
+    /// segment is wider), breaking only between segments. Each line is joined
+    /// with separators and caps.
+    fn break_row(&self, row: &Row, max_width: usize) -> Vec<Line> {
+        let rendered = self.render_row(row);
+        if rendered.is_empty() {
+            return Vec::new();
         }
 
-        if lines.is_empty() {
-            lines.push(String::new());
+        let full_line = self.join_line(&rendered);
+        if line_width(&full_line) <= max_width {
+            return vec![full_line];
         }
 
+        // Caps depend on the segments at each end, so candidate lines are
+        // measured whole
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for i in 1..rendered.len() {
+            if line_width(&self.join_line(&rendered[start..=i])) > max_width {
+                lines.push(self.join_line(&rendered[start..i]));
+                start = i;
+            }
+        }
+        lines.push(self.join_line(&rendered[start..]));
+
         lines
     }
 
-    /// Generate statusline for TUI preview with proper width calculation
-    /// This method handles ANSI escape sequences properly for ratatui rendering
+    /// First line of the unwrapped statusline as ratatui spans.
     #[cfg(feature = "tui")]
     pub fn generate_for_tui(
         &self,
         segments: Vec<(SegmentConfig, SegmentData)>,
     ) -> ratatui::text::Line<'static> {
-        use ansi_to_tui::IntoText;
-        use ratatui::text::{Line, Span};
-
-        // Use the same generate method and convert to TUI
-        let full_output = self.generate(segments);
-
-        if let Ok(text) = full_output.into_text() {
-            if let Some(line) = text.lines.into_iter().next() {
-                return line;
-            }
-        }
-
-        // Fallback to raw text
-        Line::from(vec![Span::raw(full_output)])
+        self.layout(segments, None)
+            .first()
+            .map(|line| to_tui_line(line))
+            .unwrap_or_default()
     }
 
-    /// Generate TUI-optimized text with intelligent wrapping by segment for preview
+    /// The statusline wrapped to `max_width` as ratatui text, laid out exactly
+    /// like the terminal output.
+    #[cfg(feature = "tui")]
     pub fn generate_for_tui_preview(
         &self,
         segments: Vec<(SegmentConfig, SegmentData)>,
         max_width: u16,
-    ) -> ratatui::text::Text<'_> {
-        use ansi_to_tui::IntoText;
-        use ratatui::text::{Line, Span, Text};
-
-        let enabled_segments: Vec<_> = segments
-            .into_iter()
-            .filter(|(config, _)| config.enabled)
+    ) -> ratatui::text::Text<'static> {
+        let mut lines: Vec<ratatui::text::Line<'static>> = self
+            .layout(segments, Some(max_width as usize))
+            .iter()
+            .map(|line| to_tui_line(line))
             .collect();
 
-        if enabled_segments.is_empty() {
-            return Text::from(vec![Line::default()]);
-        }
-
-        // Render each segment individually
-        let mut rendered_segments = Vec::new();
-        let mut segment_configs = Vec::new();
-
-        for (config, data) in &enabled_segments {
-            let rendered = self.render_segment(config, data);
-            if !rendered.is_empty() {
-                rendered_segments.push(rendered);
-                segment_configs.push(config.clone());
-            }
-        }
-
-        if rendered_segments.is_empty() {
-            return Text::from(vec![Line::default()]);
-        }
-
-        // Pre-calculate separators between segments
-        let mut separators = Vec::new();
-        for i in 0..rendered_segments.len().saturating_sub(1) {
-            let separator = if self.config.style.separator == "\u{e0b0}" {
-                // Powerline arrows with color transition
-                let prev_bg = segment_configs
-                    .get(i)
-                    .and_then(|config| config.colors.background.as_ref());
-                let curr_bg = segment_configs
-                    .get(i + 1)
-                    .and_then(|config| config.colors.background.as_ref());
-                self.create_powerline_arrow(prev_bg, curr_bg)
-            } else {
-                // Regular separators with white color
-                format!("\x1b[37m{}\x1b[0m", self.config.style.separator)
-            };
-            separators.push(separator);
-        }
-
-        // Intelligent line wrapping by segment
-        let mut lines: Vec<String> = Vec::new();
-        let mut current_line = String::new();
-        let mut current_width = 0usize;
-        let max_w = max_width as usize;
-
-        for i in 0..rendered_segments.len() {
-            let segment = &rendered_segments[i];
-            let segment_width = visible_width(segment);
-
-            // Force a new line in front of any forced-break segment.
-            let force_break = i > 0
-                && segment_configs
-                    .get(i)
-                    .map(|c| forces_newline(c.id))
-                    .unwrap_or(false);
-
-            if force_break && !current_line.is_empty() {
-                lines.push(current_line.clone());
-                current_line.clear();
-                current_width = 0;
-            }
-
-            // Check if adding this segment would exceed max_width
-            if current_width > 0 && current_width + segment_width > max_w {
-                // Current line would overflow, start a new line
-                lines.push(current_line.clone());
-                current_line.clear();
-                current_width = 0;
-            }
-
-            // Add the segment to current line
-            current_line.push_str(segment);
-            current_width += segment_width;
-
-            // Handle separator if not the last segment
-            if i < separators.len() {
-                let separator = &separators[i];
-                let separator_width = visible_width(separator);
-
-                // Check if next segment exists
-                if i + 1 < rendered_segments.len() {
-                    let next_segment = &rendered_segments[i + 1];
-                    let next_width = visible_width(next_segment);
-
-                    // Check if separator AND next segment both fit
-                    if current_width + separator_width + next_width <= max_w {
-                        // Both fit, add separator and continue on same line
-                        current_line.push_str(separator);
-                        current_width += separator_width;
-                    } else {
-                        // Separator and/or next segment don't fit
-                        // Don't add separator, just break line
-                        lines.push(current_line.clone());
-                        current_line.clear();
-                        current_width = 0;
-                    }
-                }
-            }
+        // Ensure we have at least one line
+        if lines.is_empty() {
+            lines.push(ratatui::text::Line::default());
         }
 
-        // Add the last line if it's not empty
-        if !current_line.is_empty() {
-            lines.push(current_line);
-        }
+        ratatui::text::Text::from(lines)
+    }
 
-        // Convert string lines to ratatui Text
-        let mut tui_lines = Vec::new();
-        for line in lines {
-            if let Ok(text) = line.into_text() {
-                for tui_line in text.lines {
-                    tui_lines.push(tui_line);
+    fn render_segment(&self, config: &SegmentConfig, data: &SegmentData) -> Line {
+        let mut spans = self.render_segment_content(config, data);
+        // Hyperlinks are escape sequences too, so monochrome output drops them
+        if self.config.style.hyperlinks && self.depth != ColorDepth::None {
+            if let Some(url) = data.metadata.get("link") {
+                for span in &mut spans {
+                    span.link = Some(url.clone());
                 }
-            } else {
-                tui_lines.push(Line::from(vec![Span::raw(line)]));
             }
         }
-
-        // Ensure we have at least one line
-        if tui_lines.is_empty() {
-            tui_lines.push(Line::default());
-        }
-
-        Text::from(tui_lines)
+        spans
     }
 
-    fn render_segment(&self, config: &SegmentConfig, data: &SegmentData) -> String {
+    fn render_segment_content(&self, config: &SegmentConfig, data: &SegmentData) -> Line {
+        // A `format` template replaces the default primary/secondary text
+        let formatted = config
+            .options
+            .get("format")
+            .and_then(|v| v.as_str())
+            .and_then(|template| render_template(template, data))
+            .map(|primary| SegmentData {
+                primary,
+                secondary: String::new(),
+                metadata: data.metadata.clone(),
+            });
+        let data = formatted.as_ref().unwrap_or(data);
+
         let icon = if let Some(dynamic_icon) = data.metadata.get("dynamic_icon") {
             dynamic_icon.clone()
         } else {
             self.get_icon(config)
         };
 
-        // Apply background color to the entire segment if set
-        if let Some(bg_color) = &config.colors.background {
-            let bg_code = self.apply_background_color(bg_color);
-
-            // Build the entire segment content first
-            let icon_colored = if let Some(icon_color) = &config.colors.icon {
-                self.apply_color(&icon, Some(icon_color))
-                    .replace("\x1b[0m", "")
-            } else {
-                icon.clone()
-            };
-
-            let text_styled = self
-                .apply_style(
-                    &data.primary,
-                    config.colors.text.as_ref(),
-                    config.styles.text_bold,
-                )
-                .replace("\x1b[0m", "");
-
-            let mut segment_content = format!(" {} {} ", icon_colored, text_styled);
-
+        let bg = config.colors.background.as_ref();
+        let icon_style = self.style(config.colors.icon.as_ref(), bg);
+        let text_style = self.text_style(config, bg);
+        // Spaces take the text color too, so they merge with plain text spans
+        let space = || Span::styled(" ", self.style(config.colors.text.as_ref(), bg));
+
+        let mut line = Line::new();
+        if bg.is_some() {
+            // The background fills the whole segment, padding included
+            push_span(&mut line, space());
+            push_span(&mut line, Span::styled(icon, icon_style));
+            push_span(&mut line, space());
+            push_span(
+                &mut line,
+                Span::styled(data.primary.clone(), text_style.clone()),
+            );
+            push_span(&mut line, space());
             if !data.secondary.is_empty() {
-                let secondary_styled = self
-                    .apply_style(
-                        &data.secondary,
-                        config.colors.text.as_ref(),
-                        config.styles.text_bold,
-                    )
-                    .replace("\x1b[0m", "");
-                segment_content.push_str(&format!("{} ", secondary_styled));
-            }
-
-            // Apply background to the entire content and reset at the end
-            format!("{}{}\x1b[49m", bg_code, segment_content)
+                push_span(&mut line, Span::styled(data.secondary.clone(), text_style));
+                push_span(&mut line, space());
+            }
         } else {
-            // No background color, use original logic
-            let icon_colored = self.apply_color(&icon, config.colors.icon.as_ref());
-            let text_styled = self.apply_style(
-                &data.primary,
-                config.colors.text.as_ref(),
-                config.styles.text_bold,
-            );
-
-            let mut segment = if data.primary.is_empty() {
-                icon_colored
-            } else {
-                format!("{} {}", icon_colored, text_styled)
-            };
-
+            push_span(&mut line, Span::styled(icon, icon_style));
+            if !data.primary.is_empty() {
+                push_span(&mut line, space());
+                push_span(
+                    &mut line,
+                    Span::styled(data.primary.clone(), text_style.clone()),
+                );
+            }
             if !data.secondary.is_empty() {
-                segment.push_str(&format!(
-                    " {}",
-                    self.apply_style(
-                        &data.secondary,
-                        config.colors.text.as_ref(),
-                        config.styles.text_bold
-                    )
-                ));
+                push_span(&mut line, space());
+                push_span(&mut line, Span::styled(data.secondary.clone(), text_style));
             }
-
-            segment
         }
+        line
     }
 
     fn get_icon(&self, config: &SegmentConfig) -> String {
@@ -442,190 +523,105 @@ impl StatusLineGenerator {
         }
     }
 
-    fn apply_color(&self, text: &str, color: Option<&AnsiColor>) -> String {
-        match color {
-            Some(AnsiColor::Color16 { c16 }) => {
-                let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
-                format!("\x1b[{}m{}\x1b[0m", code, text)
-            }
-            Some(AnsiColor::Color256 { c256 }) => {
-                format!("\x1b[38;5;{}m{}\x1b[0m", c256, text)
-            }
-            Some(AnsiColor::Rgb { r, g, b }) => {
-                format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text)
-            }
-            None => text.to_string(),
+    /// Span style with colors reduced to the output depth. Monochrome output
+    /// keeps no style at all.
+    fn style(&self, fg: Option<&AnsiColor>, bg: Option<&AnsiColor>) -> Style {
+        if self.depth == ColorDepth::None {
+            return Style::default();
+        }
+        Style {
+            fg: fg.map(|c| downsample(c, self.depth)),
+            bg: bg.map(|c| downsample(c, self.depth)),
+            ..Style::default()
         }
     }
 
-    fn apply_style(&self, text: &str, color: Option<&AnsiColor>, bold: bool) -> String {
-        let mut codes = Vec::new();
-
-        // Add style codes
-        if bold {
-            codes.push("1".to_string()); // Bold: \x1b[1m
+    /// Style of a segment's text: its color plus the configured attributes.
+    fn text_style(&self, config: &SegmentConfig, bg: Option<&AnsiColor>) -> Style {
+        if self.depth == ColorDepth::None {
+            return Style::default();
         }
-
-        // Add color codes
-        match color {
-            Some(AnsiColor::Color16 { c16 }) => {
-                let color_code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
-                codes.push(color_code.to_string());
-            }
-            Some(AnsiColor::Color256 { c256 }) => {
-                codes.push("38".to_string());
-                codes.push("5".to_string());
-                codes.push(c256.to_string());
-            }
-            Some(AnsiColor::Rgb { r, g, b }) => {
-                codes.push("38".to_string());
-                codes.push("2".to_string());
-                codes.push(r.to_string());
-                codes.push(g.to_string());
-                codes.push(b.to_string());
-            }
-            None => {}
-        }
-
-        if codes.is_empty() {
-            text.to_string()
-        } else {
-            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+        let styles = &config.styles;
+        Style {
+            bold: styles.text_bold,
+            dim: styles.text_dim,
+            italic: styles.text_italic,
+            underline: styles.text_underline,
+            underline_color: styles
+                .underline_color
+                .as_ref()
+                .map(|c| downsample(c, self.depth)),
+            strikethrough: styles.text_strikethrough,
+            inverse: styles.text_inverse,
+            ..self.style(config.colors.text.as_ref(), bg)
         }
     }
 
-    fn apply_background_color(&self, color: &AnsiColor) -> String {
-        match color {
-            AnsiColor::Color16 { c16 } => {
-                let code = if *c16 < 8 { 40 + c16 } else { 100 + (c16 - 8) };
-                format!("\x1b[{}m", code)
-            }
-            AnsiColor::Color256 { c256 } => {
-                format!("\x1b[48;5;{}m", c256)
+    /// Join rendered segments into one line with separators and row caps.
+    fn join_line(&self, rendered: &[(&SegmentConfig, Line)]) -> Line {
+        let (Some((first, _)), Some((last, _))) = (rendered.first(), rendered.last()) else {
+            return Line::new();
+        };
+
+        let mut line = Line::new();
+        push_span(&mut line, self.create_cap(true, first));
+        for (i, (config, segment)) in rendered.iter().enumerate() {
+            if i > 0 {
+                push_span(&mut line, self.create_separator(rendered[i - 1].0, config));
             }
-            AnsiColor::Rgb { r, g, b } => {
-                format!("\x1b[48;2;{};{};{}m", r, g, b)
+            for span in segment {
+                push_span(&mut line, span.clone());
             }
         }
-    }
-
-    /// Join segments with white separators (non-Powerline)
-    fn join_with_white_separators(
-        &self,
-        rendered_segments: &[String],
-        segment_configs: &[(SegmentConfig, SegmentData)],
-    ) -> String {
-        self.join_with_separators(rendered_segments, segment_configs, false)
-    }
+        push_span(&mut line, self.create_cap(false, last));
 
-    /// Join segments with Powerline arrow separators with proper color transitions
-    fn join_with_powerline_arrows(
-        &self,
-        rendered_segments: &[String],
-        segment_configs: &[(SegmentConfig, SegmentData)],
-    ) -> String {
-        self.join_with_separators(rendered_segments, segment_configs, true)
+        line
     }
 
-    /// Shared join routine: inserts `\n` before any `Cwd` segment (after the first),
-    /// otherwise uses the configured separator. For Powerline mode also resets style
-    /// at the line break so background colors don't bleed.
-    fn join_with_separators(
-        &self,
-        rendered_segments: &[String],
-        segment_configs: &[(SegmentConfig, SegmentData)],
-        powerline: bool,
-    ) -> String {
-        if rendered_segments.is_empty() {
-            return String::new();
-        }
-        if rendered_segments.len() == 1 {
-            return rendered_segments[0].clone();
-        }
-
-        let white_separator = format!("\x1b[37m{}\x1b[0m", self.config.style.separator);
-        let mut out = String::new();
-        out.push_str(&rendered_segments[0]);
-
-        for (i, segment) in rendered_segments.iter().enumerate().skip(1) {
-            // Insert newline before any forced-break segment so the row breaks cleanly.
-            let force_break = segment_configs
-                .get(i)
-                .map(|(c, _)| forces_newline(c.id))
-                .unwrap_or(false);
-
-            if force_break {
-                // Reset before the line break so previous bg/fg doesn't leak.
-                out.push_str("\x1b[0m\n");
-            } else if powerline {
-                let prev_bg = segment_configs
-                    .get(i - 1)
-                    .and_then(|(c, _)| c.colors.background.as_ref());
-                let curr_bg = segment_configs
-                    .get(i)
-                    .and_then(|(c, _)| c.colors.background.as_ref());
-                out.push_str(&self.create_powerline_arrow(prev_bg, curr_bg));
-            } else {
-                out.push_str(&white_separator);
+    /// Separator between two adjacent segments, colored according to its kind.
+    fn create_separator(&self, prev: &SegmentConfig, curr: &SegmentConfig) -> Span {
+        let separator = &self.config.style.separator;
+        let prev_bg = prev.colors.background.as_ref();
+        let curr_bg = curr.colors.background.as_ref();
+
+        let style = match separator_kind(separator) {
+            // Glyph in the previous background, on the next one
+            SeparatorKind::Filled => self.style(prev_bg, curr_bg),
+            // A thin glyph between equal backgrounds would vanish, so draw it in
+            // the text color on that shared background instead
+            SeparatorKind::Thin if prev_bg == curr_bg => {
+                let fg = prev.colors.text.as_ref().unwrap_or(&WHITE);
+                self.style(Some(fg), curr_bg)
             }
-
-            out.push_str(segment);
-        }
-
-        // Reset colors at the end so background doesn't bleed past the line.
-        if powerline {
-            out.push_str("\x1b[0m");
-        }
-
-        out
+            SeparatorKind::Thin => self.style(prev_bg, curr_bg),
+            SeparatorKind::Text => self.style(Some(&WHITE), None),
+        };
+        Span::styled(separator.clone(), style)
     }
 
-    /// Create a Powerline arrow with proper color transition
-    fn create_powerline_arrow(
-        &self,
-        prev_bg: Option<&AnsiColor>,
-        curr_bg: Option<&AnsiColor>,
-    ) -> String {
-        let arrow_char = "\u{e0b0}";
+    /// Start or end cap of a line, drawn in the adjacent segment's background.
+    ///
+    /// Without a configured cap, powerline separators open and close each line
+    /// with their own filled shape, so a row never ends in a bare background;
+    /// this is skipped next to segments without a background.
+    fn create_cap(&self, opening: bool, segment: &SegmentConfig) -> Span {
+        let style = &self.config.style;
+        let cap = if opening {
+            &style.start_cap
+        } else {
+            &style.end_cap
+        };
+        let bg = segment.colors.background.as_ref();
 
-        match (prev_bg, curr_bg) {
-            (Some(prev), Some(curr)) => {
-                // Arrow foreground = previous segment's background
-                // Arrow background = current segment's background
-                let fg_code = self.color_to_foreground_code(prev);
-                let bg_code = self.apply_background_color(curr);
-                format!("{}{}{}\x1b[0m", bg_code, fg_code, arrow_char)
-            }
-            (Some(prev), None) => {
-                // Previous segment has background, current doesn't
-                let fg_code = self.color_to_foreground_code(prev);
-                format!("{}{}\x1b[0m", fg_code, arrow_char)
-            }
-            (None, Some(curr)) => {
-                // Current segment has background, previous doesn't
-                let bg_code = self.apply_background_color(curr);
-                format!("{}{}\x1b[0m", bg_code, arrow_char)
-            }
-            (None, None) => {
-                // Neither segment has background color
-                arrow_char.to_string()
-            }
+        if !cap.is_empty() {
+            return Span::styled(cap, self.style(Some(bg.unwrap_or(&WHITE)), None));
         }
-    }
-
-    /// Convert AnsiColor to foreground color code
-    fn color_to_foreground_code(&self, color: &AnsiColor) -> String {
-        match color {
-            AnsiColor::Color16 { c16 } => {
-                let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
-                format!("\x1b[{}m", code)
-            }
-            AnsiColor::Color256 { c256 } => {
-                format!("\x1b[38;5;{}m", c256)
-            }
-            AnsiColor::Rgb { r, g, b } => {
-                format!("\x1b[38;2;{};{};{}m", r, g, b)
-            }
+        match (bg, edge_glyphs(&style.separator)) {
+            (Some(bg), Some((open, close))) => Span::styled(
+                if opening { open } else { close },
+                self.style(Some(bg), None),
+            ),
+            _ => Span::plain(""),
         }
     }
 }
@@ -636,6 +632,7 @@ pub fn collect_all_segments(
 ) -> Vec<(SegmentConfig, SegmentData)> {
     use crate::core::segments::*;
 
+    let transcript = Transcript::new(&input.transcript_path);
     let mut results = Vec::new();
 
     for segment_config in &config.segments {
@@ -647,15 +644,15 @@ pub fn collect_all_segments(
         let segment_data = match segment_config.id {
             crate::config::SegmentId::Model => {
                 let segment = ModelSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Directory => {
                 let segment = DirectorySegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Cwd => {
                 let segment = CwdSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Git => {
                 let show_sha = segment_config
@@ -664,39 +661,39 @@ pub fn collect_all_segments(
                     .and_then(|v| v.as_bool())
                     .unwrap_or(false);
                 let segment = GitSegment::new().with_sha(show_sha);
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::ContextWindow => {
                 let segment = ContextWindowSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Usage => {
                 let segment = UsageSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Cost => {
                 let segment = CostSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Session => {
                 let segment = SessionSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::OutputStyle => {
                 let segment = OutputStyleSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Update => {
                 let segment = UpdateSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Balance => {
                 let segment = BalanceSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Used => {
                 let segment = UsedSegment::new();
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
             crate::config::SegmentId::Branding => {
                 // Branding intentionally puts the brand name in `icon.*`; primary is left blank
@@ -708,7 +705,7 @@ pub fn collect_all_segments(
                     .unwrap_or("")
                     .to_string();
                 let segment = BrandingSegment::new().with_text(text);
-                segment.collect(input)
+                segment.collect(input, &transcript)
             }
         };
 
@@ -719,3 +716,384 @@ pub fn collect_all_segments(
 
     results
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::width::visible_width;
+    use std::collections::HashMap;
+
+    fn segments(config: &Config) -> Vec<(SegmentConfig, SegmentData)> {
+        config
+            .segments
+            .iter()
+            .map(|s| {
+                let data = SegmentData {
+                    primary: format!("{:?}", s.id),
+                    secondary: String::new(),
+                    metadata: HashMap::new(),
+                };
+                (s.clone(), data)
+            })
+            .collect()
+    }
+
+    /// Default config on a single row with only `ids` enabled.
+    fn config_with(ids: &[SegmentId]) -> Config {
+        only(Config::default(), ids)
+    }
+
+    fn only(mut config: Config, ids: &[SegmentId]) -> Config {
+        config.rows = Vec::new();
+        for segment in &mut config.segments {
+            segment.enabled = ids.contains(&segment.id);
+        }
+        config
+    }
+
+    #[test]
+    fn rows_follow_config_and_collect_unlisted_segments() {
+        let mut config = config_with(&[
+            SegmentId::Model,
+            SegmentId::Git,
+            SegmentId::Cost,
+            SegmentId::Session,
+        ]);
+        config.rows = vec![
+            vec![SegmentId::Git, SegmentId::Model],
+            vec![],
+            vec![SegmentId::Cost],
+        ];
+
+        let generator = StatusLineGenerator::new(config.clone());
+        let rows: Vec<Vec<SegmentId>> = generator
+            .split_rows(segments(&config))
+            .into_iter()
+            .map(|row| row.into_iter().map(|(c, _)| c.id).collect())
+            .collect();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![SegmentId::Git, SegmentId::Model],
+                vec![SegmentId::Cost, SegmentId::Session],
+            ]
+        );
+    }
+
+    #[test]
+    fn right_group_is_flush_right_and_moves_down_when_it_does_not_fit() {
+        let mut config = config_with(&[SegmentId::Model, SegmentId::Cost]);
+        config.style.separator = "|".to_string();
+        for segment in &mut config.segments {
+            segment.colors.background = None;
+            if segment.id == SegmentId::Cost {
+                segment.align = Align::Right;
+            }
+        }
+        let generator = StatusLineGenerator::new(config.clone());
+
+        let lines = generator.generate_wrapped(segments(&config), 40);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(visible_width(&lines[0]), 40);
+        assert!(lines[0].trim_end_matches("\x1b[0m").ends_with("Cost"));
+
+        let narrow = generator.generate_wrapped(segments(&config), 12);
+        assert_eq!(narrow.len(), 2);
+        assert_eq!(visible_width(&narrow[1]), 12);
+    }
+
+    #[test]
+    fn narrow_rows_compact_then_hide_low_priority_segments() {
+        let mut config = config_with(&[SegmentId::Model, SegmentId::Cwd, SegmentId::Cost]);
+        config.style.separator = "|".to_string();
+        for segment in &mut config.segments {
+            match segment.id {
+                SegmentId::Cwd => segment.priority = Some(5),
+                SegmentId::Cost => segment.priority = Some(1),
+                _ => {}
+            }
+        }
+        let mut row = segments(&config);
+        for (segment, data) in &mut row {
+            if segment.id == SegmentId::Cwd {
+                data.primary = "~/work/some/long/project".to_string();
+                data.metadata
+                    .insert("compact".to_string(), "project".to_string());
+            }
+        }
+        let row: Row = row.into_iter().filter(|(c, _)| c.enabled).collect();
+        let generator = StatusLineGenerator::new(config);
+
+        let ids = |row: &Row| {
+            row.iter()
+                .map(|(c, d)| (c.id, d.primary.clone()))
+                .collect::<Vec<_>>()
+        };
+        let full_width = generator.row_width(&row);
+        assert_eq!(ids(&generator.fit_row(row.clone(), full_width)), ids(&row));
+
+        // Cost has no compact form, so Cwd is compacted next
+        let compacted = generator.fit_row(row.clone(), full_width - 5);
+        assert!(compacted
+            .iter()
+            .any(|(c, d)| c.id == SegmentId::Cwd && d.primary == "project"));
+        assert_eq!(compacted.len(), 3);
+
+        // Still too narrow: the lowest priority segment goes first
+        let dropped = generator.fit_row(row, 20);
+        assert!(dropped.iter().all(|(c, _)| c.id != SegmentId::Cost));
+        assert!(dropped.iter().any(|(c, _)| c.id == SegmentId::Model));
+    }
+
+    #[test]
+    fn powerline_separators_blend_backgrounds_and_caps_every_line() {
+        let mut config = config_with(&[SegmentId::Model, SegmentId::Git]);
+        config.style.separator = "\u{e0b4}".to_string();
+        config.style.start_cap = "\u{e0b6}".to_string();
+        config.style.color_depth = ColorDepth::Truecolor;
+        let bg = |id: SegmentId| {
+            config
+                .segments
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.colors.background.clone())
+        };
+        let (model_bg, git_bg) = (bg(SegmentId::Model), bg(SegmentId::Git));
+        let generator = StatusLineGenerator::new(config.clone());
+
+        let lines = generator.layout(segments(&config), None);
+        let line = &lines[0];
+        assert_eq!(line[0].text, "\u{e0b6}");
+        assert_eq!(line[0].style.fg, model_bg);
+        let transition = line.iter().find(|span| span.text == "\u{e0b4}").unwrap();
+        assert_eq!(transition.style.fg, model_bg);
+        assert_eq!(transition.style.bg, git_bg);
+
+        // Forced onto two lines, each line gets its own cap
+        let lines = generator.layout(segments(&config), Some(12));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1][0].text, "\u{e0b6}");
+        assert_eq!(lines[1][0].style.fg, git_bg);
+    }
+
+    #[test]
+    fn wrapped_lines_open_and_close_with_the_separator_shape() {
+        let mut config = config_with(&[SegmentId::Model, SegmentId::Git, SegmentId::Cost]);
+        config.rows = vec![
+            vec![SegmentId::Model, SegmentId::Git],
+            vec![SegmentId::Cost],
+        ];
+        config.style.separator = "\u{e0b1}".to_string();
+        config.style.color_depth = ColorDepth::Truecolor;
+        for segment in &mut config.segments {
+            if segment.id == SegmentId::Cost {
+                segment.colors.background = None;
+            }
+        }
+        let bg = |id: SegmentId| {
+            config
+                .segments
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.colors.background.clone())
+        };
+        let generator = StatusLineGenerator::new(config.clone());
+
+        // Width-driven break inside the first row, forced break before Cost
+        let lines = generator.layout(segments(&config), Some(14));
+        assert_eq!(lines.len(), 3);
+        for (line, id) in lines.iter().zip([SegmentId::Model, SegmentId::Git]) {
+            let (first, last) = (&line[0], &line[line.len() - 1]);
+            assert_eq!(first.text, "\u{e0b2}");
+            assert_eq!(last.text, "\u{e0b0}");
+            assert_eq!(first.style.fg, bg(id));
+            assert_eq!(last.style.fg, bg(id));
+            assert_eq!(last.style.bg, None);
+        }
+        // No background, nothing to close
+        assert!(lines[2].iter().all(|span| !span.text.contains('\u{e0b0}')));
+    }
+
+    #[test]
+    fn gradient_spans_visible_segments_and_keeps_threshold_backgrounds() {
+        let rgb = |r, g, b| AnsiColor::Rgb { r, g, b };
+        let mut config = config_with(&[SegmentId::Model, SegmentId::Git, SegmentId::Cost]);
+        config.style.gradient = vec![rgb(0, 0, 0), rgb(200, 200, 200)];
+        let warning = AnsiColor::Color16 { c16: 1 };
+        for segment in &mut config.segments {
+            if segment.id == SegmentId::Cost {
+                segment.thresholds = vec![crate::config::ThresholdRule {
+                    key: None,
+                    above: Some(1.0),
+                    below: None,
+                    colors: crate::config::ColorConfig {
+                        icon: None,
+                        text: None,
+                        background: Some(warning.clone()),
+                    },
+                }];
+            }
+        }
+        let generator = StatusLineGenerator::new(config.clone());
+        let backgrounds = |cost: &str| {
+            let mut row = segments(&config);
+            for (segment, data) in &mut row {
+                if segment.id == SegmentId::Cost {
+                    data.metadata.insert("cost".to_string(), cost.to_string());
+                }
+            }
+            let row = generator.split_rows(row).remove(0);
+            generator
+                .apply_gradient(row)
+                .into_iter()
+                .map(|(c, _)| (c.id, c.colors.background))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            backgrounds("0.5"),
+            vec![
+                (SegmentId::Model, Some(rgb(0, 0, 0))),
+                (SegmentId::Cost, Some(rgb(100, 100, 100))),
+                (SegmentId::Git, Some(rgb(200, 200, 200))),
+            ]
+        );
+        assert_eq!(backgrounds("2")[1], (SegmentId::Cost, Some(warning)));
+    }
+
+    #[test]
+    fn rows_are_fitted_with_the_padding_a_gradient_adds() {
+        let theme = crate::ui::themes::ThemePresets::get_minimal();
+        let mut config = only(theme, &[SegmentId::Model, SegmentId::Cwd]);
+        config.style.gradient = vec![
+            AnsiColor::Rgb { r: 0, g: 0, b: 0 },
+            AnsiColor::Rgb {
+                r: 90,
+                g: 90,
+                b: 90,
+            },
+        ];
+        let mut row = segments(&config);
+        for (segment, data) in &mut row {
+            if segment.id == SegmentId::Cwd {
+                data.primary = "~/work/some/long/project".to_string();
+                data.metadata
+                    .insert("compact".to_string(), "project".to_string());
+            }
+        }
+        let generator = StatusLineGenerator::new(config.clone());
+        let full = generator.layout(row.clone(), None).remove(0);
+
+        // One column short: compacted onto one line rather than wrapped
+        let lines = generator.layout(row, Some(line_width(&full) - 1));
+        assert_eq!(lines.len(), 1);
+        assert!(line_width(&lines[0]) < line_width(&full));
+        let text: String = lines[0].iter().map(|span| span.text.as_str()).collect();
+        assert!(text.contains("project") && !text.contains("long"));
+    }
+
+    #[test]
+    fn monochrome_output_has_no_escape_sequences() {
+        let mut config = config_with(&[SegmentId::Model, SegmentId::Git, SegmentId::Cost]);
+        config.style.color_depth = ColorDepth::None;
+        config.style.start_cap = "\u{e0b6}".to_string();
+        for segment in &mut config.segments {
+            if segment.id == SegmentId::Cost {
+                segment.align = Align::Right;
+            }
+        }
+        let generator = StatusLineGenerator::new(config.clone());
+
+        let line = generator.generate(segments(&config));
+        assert!(!line.contains('\x1b'));
+        assert!(line.starts_with('\u{e0b6}'));
+        assert!(line.contains("Model"));
+
+        for width in [80, 20] {
+            let lines = generator.generate_wrapped(segments(&config), width);
+            assert!(lines.iter().all(|line| !line.contains('\x1b')));
+        }
+    }
+
+    #[test]
+    fn tmux_output_keeps_every_row_on_one_line() {
+        let mut config = Config::default();
+        for segment in &mut config.segments {
+            segment.enabled = true;
+        }
+        assert!(config.rows.len() > 1);
+        let row_starts: Vec<String> = config
+            .rows
+            .iter()
+            .filter_map(|row| row.first())
+            .map(|id| format!("{:?}", id))
+            .collect();
+
+        let terminal = StatusLineGenerator::new(config.clone()).generate(segments(&config));
+        assert!(terminal.contains('\n'));
+
+        let tmux = StatusLineGenerator::new(config.clone())
+            .with_target(RenderTarget::Tmux)
+            .generate(segments(&config));
+        assert!(!tmux.contains('\n'));
+        for text in &row_starts {
+            assert!(tmux.contains(text.as_str()), "{} missing", text);
+        }
+    }
+
+    #[test]
+    fn hyperlinks_wrap_segments_without_taking_width() {
+        let mut config = config_with(&[SegmentId::Cwd]);
+        config.style.color_depth = ColorDepth::Truecolor;
+        let mut row: Row = segments(&config)
+            .into_iter()
+            .filter(|(c, _)| c.id == SegmentId::Cwd)
+            .collect();
+        let generator = StatusLineGenerator::new(config);
+        let plain_width = generator.row_width(&row);
+        row[0]
+            .1
+            .metadata
+            .insert("link".to_string(), "file:///tmp/project".to_string());
+        let line = render_line(
+            &generator.join_line(&generator.render_row(&row)),
+            RenderTarget::Terminal,
+        );
+        assert!(line.contains("\x1b]8;;file:///tmp/project\x1b\\"));
+        assert_eq!(visible_width(&line), plain_width);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn tui_preview_shows_the_terminal_lines() {
+        let mut config = config_with(&[
+            SegmentId::Model,
+            SegmentId::Cwd,
+            SegmentId::Git,
+            SegmentId::Cost,
+        ]);
+        config.style.separator = "\u{e0b0}".to_string();
+        config.style.color_depth = ColorDepth::Color256;
+        for segment in &mut config.segments {
+            if segment.id == SegmentId::Cost {
+                segment.align = Align::Right;
+            }
+        }
+        let generator = StatusLineGenerator::new(config.clone());
+        for width in [80, 30, 16] {
+            let terminal = generator.generate_wrapped(segments(&config), width);
+            let preview = generator.generate_for_tui_preview(segments(&config), width as u16);
+            let preview: Vec<String> = preview
+                .lines
+                .iter()
+                .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect();
+            let terminal: Vec<String> = terminal
+                .iter()
+                .map(|line| crate::core::width::strip_ansi(line))
+                .collect();
+            assert_eq!(preview, terminal);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+// Serialization of laid-out lines for each output target.
+//
+// The same span lines are written as raw ANSI for the terminal, or as the
+// native markup of tmux and shell prompts, so segments, separators and caps
+// look exactly as they do in Claude Code:
+//
+//   tmux  status-right '#(eflowcodeline --format tmux --last-session)'
+//   zsh   PROMPT='$(eflowcodeline --format zsh --last-session) '   (prompt_subst)
+//   bash  PROMPT_COMMAND='PS1="$(eflowcodeline --format bash --last-session) "'
+
+use crate::config::{AnsiColor, Underline};
+use crate::core::hyperlink;
+use crate::core::layout::{Span, Style};
+
+/// Markup dialect of the rendered statusline.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RenderTarget {
+    /// Raw ANSI escape sequences.
+    #[default]
+    Terminal,
+    /// tmux `#[fg=...,bg=...]` style markup.
+    Tmux,
+    /// zsh prompt escapes (`%F{}`, `%K{}`) for colors, text attributes as raw
+    /// SGR codes inside `%{ %}`.
+    Zsh,
+    /// bash prompt with escapes wrapped in `\[ \]`.
+    Bash,
+}
+
+/// Write a line in `target` markup. Styled lines end with a full reset; a line
+/// without any style or link is emitted as plain text.
+pub fn render_line(line: &[Span], target: RenderTarget) -> String {
+    let mut out = String::new();
+    let mut style = Style::default();
+    let mut link: Option<&str> = None;
+
+    for span in line {
+        if span.link.as_deref() != link {
+            if link.is_some() {
+                push_escape(&mut out, hyperlink::CLOSE, target);
+            }
+            if let Some(url) = &span.link {
+                push_escape(&mut out, &hyperlink::open(url), target);
+            }
+            link = span.link.as_deref();
+        }
+        if span.style != style {
+            push_style(&mut out, &style, &span.style, target);
+            style = span.style.clone();
+        }
+        for ch in span.text.chars() {
+            push_text(&mut out, ch, target);
+        }
+    }
+
+    if link.is_some() {
+        push_escape(&mut out, hyperlink::CLOSE, target);
+    }
+    if style != Style::default() {
+        push_style(&mut out, &style, &Style::default(), target);
+    }
+    out
+}
+
+fn push_text(out: &mut String, ch: char, target: RenderTarget) {
+    match (target, ch) {
+        (RenderTarget::Tmux, '#') => out.push_str("##"),
+        (RenderTarget::Zsh, '%') => out.push_str("%%"),
+        // PS1 is backslash-decoded and then expanded like a double-quoted
+        // string, so these need two levels of escaping
+        (RenderTarget::Bash, '\\') => out.push_str("\\\\\\\\"),
+        (RenderTarget::Bash, '$') => out.push_str("\\\\$"),
+        (RenderTarget::Bash, '`') => out.push_str("\\\\`"),
+        _ => out.push(ch),
+    }
+}
+
+/// A raw escape sequence, marked zero-width for shell prompts. tmux status
+/// lines cannot carry them.
+fn push_escape(out: &mut String, escape: &str, target: RenderTarget) {
+    match target {
+        RenderTarget::Terminal => out.push_str(escape),
+        RenderTarget::Zsh => out.push_str(&format!("%{{{}%}}", escape)),
+        RenderTarget::Bash => out.push_str(&format!("\\[{}\\]", escape)),
+        RenderTarget::Tmux => {}
+    }
+}
+
+/// Switch from `prev` to `style`.
+fn push_style(out: &mut String, prev: &Style, style: &Style, target: RenderTarget) {
+    match target {
+        RenderTarget::Terminal | RenderTarget::Bash => {
+            let mut codes = vec!["0".to_string()];
+            codes.extend(sgr_attributes(style));
+            if let Some(fg) = &style.fg {
+                codes.push(sgr_color(fg, 30, 90, 38));
+            }
+            if let Some(bg) = &style.bg {
+                codes.push(sgr_color(bg, 40, 100, 48));
+            }
+            push_escape(out, &format!("\x1b[{}m", codes.join(";")), target);
+        }
+        RenderTarget::Tmux if *style == Style::default() => out.push_str("#[default]"),
+        RenderTarget::Tmux => {
+            let mut parts = vec!["default".to_string()];
+            if let Some(fg) = &style.fg {
+                parts.push(format!("fg={}", tmux_color(fg)));
+            }
+            if let Some(bg) = &style.bg {
+                parts.push(format!("bg={}", tmux_color(bg)));
+            }
+            let underline = match style.underline {
+                Underline::None => None,
+                Underline::Single => Some("underscore"),
+                Underline::Double => Some("double-underscore"),
+                Underline::Curly => Some("curly-underscore"),
+                Underline::Dotted => Some("dotted-underscore"),
+                Underline::Dashed => Some("dashed-underscore"),
+            };
+            let attributes = [
+                (style.bold, "bold"),
+                (style.dim, "dim"),
+                (style.italic, "italics"),
+                (style.inverse, "reverse"),
+                (style.strikethrough, "strikethrough"),
+            ];
+            parts.extend(
+                attributes
+                    .into_iter()
+                    .filter(|(enabled, _)| *enabled)
+                    .map(|(_, name)| name)
+                    .chain(underline)
+                    .map(str::to_string),
+            );
+            if let Some(color) = &style.underline_color {
+                parts.push(format!("us={}", tmux_color(color)));
+            }
+            out.push_str(&format!("#[{}]", parts.join(",")));
+        }
+        RenderTarget::Zsh => {
+            match &style.fg {
+                Some(fg) => out.push_str(&format!("%F{{{}}}", zsh_color(fg))),
+                None => out.push_str("%f"),
+            }
+            match &style.bg {
+                Some(bg) => out.push_str(&format!("%K{{{}}}", zsh_color(bg))),
+                None => out.push_str("%k"),
+            }
+            // zsh has no escapes for most attributes, so all of them are raw
+            // SGR codes, switched off one by one to leave zsh's colors alone
+            let mut codes = sgr_attributes_off(prev);
+            codes.extend(sgr_attributes(style));
+            if sgr_attributes(prev) != sgr_attributes(style) {
+                push_escape(out, &format!("\x1b[{}m", codes.join(";")), target);
+            }
+        }
+    }
+}
+
+/// SGR parameters turning on the attributes of `style`.
+fn sgr_attributes(style: &Style) -> Vec<String> {
+    let underline = match style.underline {
+        Underline::None => None,
+        Underline::Single => Some("4"),
+        Underline::Double => Some("4:2"),
+        Underline::Curly => Some("4:3"),
+        Underline::Dotted => Some("4:4"),
+        Underline::Dashed => Some("4:5"),
+    };
+    let attributes = [
+        (style.bold, "1"),
+        (style.dim, "2"),
+        (style.italic, "3"),
+        (style.inverse, "7"),
+        (style.strikethrough, "9"),
+    ];
+    let mut codes: Vec<String> = attributes
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, code)| code)
+        .chain(underline)
+        .map(str::to_string)
+        .collect();
+    if let Some(color) = &style.underline_color {
+        codes.push(match color {
+            AnsiColor::Color16 { c16: n } | AnsiColor::Color256 { c256: n } => {
+                format!("58;5;{}", n)
+            }
+            AnsiColor::Rgb { r, g, b } => format!("58;2;{};{};{}", r, g, b),
+        });
+    }
+    codes
+}
+
+/// SGR parameters turning off the attributes of `style`.
+fn sgr_attributes_off(style: &Style) -> Vec<String> {
+    let attributes = [
+        (style.bold || style.dim, "22"),
+        (style.italic, "23"),
+        (style.underline != Underline::None, "24"),
+        (style.inverse, "27"),
+        (style.strikethrough, "29"),
+        (style.underline_color.is_some(), "59"),
+    ];
+    attributes
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, code)| code.to_string())
+        .collect()
+}
+
+/// SGR parameters of a color: `base`/`bright` offsets for the 16 basic colors,
+/// `extended` (38 or 48) for 256-color and RGB values.
+fn sgr_color(color: &AnsiColor, base: u8, bright: u8, extended: u8) -> String {
+    match color {
+        AnsiColor::Color16 { c16 } if *c16 < 8 => (base + c16).to_string(),
+        AnsiColor::Color16 { c16 } => (bright + (c16 - 8)).to_string(),
+        AnsiColor::Color256 { c256 } => format!("{};5;{}", extended, c256),
+        AnsiColor::Rgb { r, g, b } => format!("{};2;{};{};{}", extended, r, g, b),
+    }
+}
+
+fn tmux_color(color: &AnsiColor) -> String {
+    match color {
+        AnsiColor::Color16 { c16: n } | AnsiColor::Color256 { c256: n } => format!("colour{}", n),
+        AnsiColor::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+fn zsh_color(color: &AnsiColor) -> String {
+    match color {
+        AnsiColor::Color16 { c16: n } | AnsiColor::Color256 { c256: n } => n.to_string(),
+        AnsiColor::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line() -> Vec<Span> {
+        let style = Style {
+            fg: Some(AnsiColor::Rgb { r: 255, g: 0, b: 0 }),
+            bg: Some(AnsiColor::Color256 { c256: 24 }),
+            bold: true,
+            ..Style::default()
+        };
+        vec![Span::styled(" 50% #1 ", style), Span::plain("$HOME")]
+    }
+
+    #[test]
+    fn tmux_and_zsh_use_native_markup() {
+        assert_eq!(
+            render_line(&line(), RenderTarget::Tmux),
+            "#[default,fg=#ff0000,bg=colour24,bold] 50% ##1 #[default]$HOME"
+        );
+        assert_eq!(
+            render_line(&line(), RenderTarget::Zsh),
+            "%F{#ff0000}%K{24}%{\x1b[1m%} 50%% #1 %f%k%{\x1b[22m%}$HOME"
+        );
+    }
+
+    #[test]
+    fn ansi_escapes_are_wrapped_for_bash_only() {
+        assert_eq!(
+            render_line(&line(), RenderTarget::Terminal),
+            "\x1b[0;1;38;2;255;0;0;48;5;24m 50% #1 \x1b[0m$HOME"
+        );
+        assert_eq!(
+            render_line(&line(), RenderTarget::Bash),
+            "\\[\x1b[0;1;38;2;255;0;0;48;5;24m\\] 50% #1 \\[\x1b[0m\\]\\\\$HOME"
+        );
+        assert_eq!(
+            render_line(&[Span::plain("plain")], RenderTarget::Terminal),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn text_attributes_map_to_each_target() {
+        let style = Style {
+            italic: true,
+            underline: Underline::Curly,
+            underline_color: Some(AnsiColor::Color16 { c16: 1 }),
+            ..Style::default()
+        };
+        let line = [Span::styled("x", style)];
+        assert_eq!(
+            render_line(&line, RenderTarget::Terminal),
+            "\x1b[0;3;4:3;58;5;1mx\x1b[0m"
+        );
+        assert_eq!(
+            render_line(&line, RenderTarget::Tmux),
+            "#[default,italics,curly-underscore,us=colour1]x#[default]"
+        );
+        assert_eq!(
+            render_line(&line, RenderTarget::Zsh),
+            "%f%k%{\x1b[3;4:3;58;5;1m%}x%f%k%{\x1b[23;24;59m%}"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+// Threshold-based dynamic colors.
+//
+// A segment's `thresholds` rules are checked against a numeric metadata value
+// before rendering; every matching rule overlays its colors on the segment's
+// static `ColorConfig`, so later rules win:
+//
+//   [[segments.thresholds]]
+//   above = 50
+//   colors.background = { c16 = 3 }
+//
+//   [[segments.thresholds]]
+//   above = 80
+//   colors.background = { c16 = 1 }
+
+use super::segments::SegmentData;
+use crate::config::{SegmentConfig, SegmentId, ThresholdRule};
+
+/// Metadata key a rule reads when it does not name one.
+pub fn default_metric(id: SegmentId) -> Option<&'static str> {
+    match id {
+        SegmentId::ContextWindow => Some("percentage"),
+        SegmentId::Usage => Some("five_hour_utilization"),
+        SegmentId::Cost => Some("cost"),
+        SegmentId::Balance => Some("balance"),
+        SegmentId::Used => Some("used"),
+        _ => None,
+    }
+}
+
+/// Whether `rule` matches the collected data of a segment.
+pub fn rule_matches(id: SegmentId, rule: &ThresholdRule, data: &SegmentData) -> bool {
+    let Some(key) = rule.key.as_deref().or_else(|| default_metric(id)) else {
+        return false;
+    };
+    let Some(value) = data.metadata.get(key).and_then(|v| v.parse::<f64>().ok()) else {
+        return false;
+    };
+
+    // Unlimited balances carry no meaningful amount
+    if data.metadata.get("is_unlimited").map(String::as_str) == Some("true") {
+        return false;
+    }
+
+    if rule.above.is_none() && rule.below.is_none() {
+        return false;
+    }
+    let above = match rule.above {
+        Some(above) => value >= above,
+        None => true,
+    };
+    let below = match rule.below {
+        Some(below) => value < below,
+        None => true,
+    };
+    above && below
+}
+
+/// Segment config with the colors of every matching threshold rule applied.
+pub fn apply_thresholds(mut config: SegmentConfig, data: &SegmentData) -> SegmentConfig {
+    let rules = std::mem::take(&mut config.thresholds);
+    for rule in &rules {
+        if rule_matches(config.id, rule, data) {
+            if let Some(icon) = &rule.colors.icon {
+                config.colors.icon = Some(icon.clone());
+            }
+            if let Some(text) = &rule.colors.text {
+                config.colors.text = Some(text.clone());
+            }
+            if let Some(background) = &rule.colors.background {
+                config.colors.background = Some(background.clone());
+            }
+        }
+    }
+    config.thresholds = rules;
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AnsiColor, ColorConfig};
+    use std::collections::HashMap;
+
+    fn rule(above: Option<f64>, below: Option<f64>, c16: u8) -> ThresholdRule {
+        ThresholdRule {
+            key: None,
+            above,
+            below,
+            colors: ColorConfig {
+                icon: None,
+                text: None,
+                background: Some(AnsiColor::Color16 { c16 }),
+            },
+        }
+    }
+
+    fn data(key: &str, value: &str) -> SegmentData {
+        SegmentData {
+            primary: String::new(),
+            secondary: String::new(),
+            metadata: HashMap::from([(key.to_string(), value.to_string())]),
+        }
+    }
+
+    #[test]
+    fn later_matching_rules_win() {
+        let mut config = crate::ui::themes::theme_default::context_window_segment();
+        config.thresholds = vec![rule(Some(50.0), None, 3), rule(Some(80.0), None, 1)];
+
+        let low = apply_thresholds(config.clone(), &data("percentage", "12.5"));
+        assert_eq!(low.colors.background, config.colors.background);
+
+        let mid = apply_thresholds(config.clone(), &data("percentage", "60"));
+        assert_eq!(mid.colors.background, Some(AnsiColor::Color16 { c16: 3 }));
+
+        let high = apply_thresholds(config, &data("percentage", "95"));
+        assert_eq!(high.colors.background, Some(AnsiColor::Color16 { c16: 1 }));
+    }
+
+    #[test]
+    fn below_rules_and_non_numeric_values() {
+        let low_balance = rule(None, Some(5.0), 208);
+        assert!(rule_matches(
+            SegmentId::Balance,
+            &low_balance,
+            &data("balance", "3.2")
+        ));
+        assert!(!rule_matches(
+            SegmentId::Balance,
+            &low_balance,
+            &data("balance", "12")
+        ));
+        assert!(!rule_matches(
+            SegmentId::ContextWindow,
+            &rule(Some(80.0), None, 1),
+            &data("percentage", "-")
+        ));
+        // Segments without a default metric need an explicit key
+        assert!(!rule_matches(
+            SegmentId::Git,
+            &rule(Some(0.0), None, 1),
+            &data("ahead", "2")
+        ));
+    }
+}
@@ -0,0 +1,607 @@
+// Claude Code transcripts, read once per invocation and shared by all segments.
+//
+// Transcripts are append-only JSONL files that grow to several megabytes in
+// long sessions. Most runs only need their latest state: the first read of a
+// file scans backwards from EOF until it finds the last assistant usage entry
+// and parses forward from there. Subagent totals, compactions and the first
+// turn's usage can sit anywhere before it, so the earlier lines are streamed
+// through once as well, and parsed only when they carry one of those markers.
+// That first read is linear in the file size; a small on-disk index remembers
+// where the most recently read files were left, so it happens once per file:
+//
+//   ~/.claude/eflowcodeline/cache/transcript_index.json
+//   { "<path>": { "inode", "size", "last_offset", "last_usage", ... } }
+//
+// Later runs read only the bytes appended since. A file that shrank or was
+// replaced (new inode) is read from the start again. Summary chains that lead
+// into other session files are resolved by `uuid_index`.
+//
+// Subagent (sidechain) entries never count as the main thread's context;
+// their usage is added up separately. After a compaction the last reported
+// usage is stale until the next assistant message, so the context falls back
+// to the size the compaction left, estimated when Claude Code did not record
+// it.
+
+use crate::config::TranscriptEntry;
+use crate::core::uuid_index;
+use memchr::memmem;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs::{self, File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bytes read per step when scanning backwards.
+const BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Transcripts remembered by the index; the least recently read are dropped.
+const MAX_INDEXED: usize = 200;
+
+/// Latest state of a transcript.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TailState {
+    /// Context tokens of the last assistant message with usage.
+    pub last_usage: Option<u32>,
+    /// Byte offset of the line holding `last_usage`.
+    pub last_offset: Option<u64>,
+    /// `leafUuid` of the summary entry the transcript ends with, if any.
+    pub summary_leaf: Option<String>,
+    // No serde defaults below: an index written before a field existed fails
+    // to load and is rebuilt instead of reporting zeros
+    /// Tokens processed by subagent turns.
+    pub subagent_tokens: u64,
+    /// Assistant turns taken by subagents.
+    pub subagent_turns: u32,
+    /// Compactions in this transcript.
+    pub compactions: u32,
+    pub last_compaction: Option<Compaction>,
+    /// The last compaction came after `last_usage`.
+    pub compacted_since_usage: bool,
+    /// Context tokens at `summary_leaf`, resolved once per leaf.
+    pub summary_usage: Option<u32>,
+    /// Cache reads plus writes of the first main-thread turn: the system
+    /// prompt, tools and memory files every request starts with.
+    pub prefix_tokens: Option<u32>,
+}
+
+/// Context size around one compaction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Compaction {
+    pub pre_tokens: Option<u32>,
+    /// Reported by Claude Code, or estimated from the summary it left.
+    pub post_tokens: Option<u32>,
+    pub estimated: bool,
+}
+
+impl Compaction {
+    pub fn reclaimed_tokens(&self) -> Option<u32> {
+        Some(self.pre_tokens?.saturating_sub(self.post_tokens?))
+    }
+}
+
+impl TailState {
+    /// Tokens currently in the main thread's context.
+    pub fn context_tokens(&self) -> Option<u32> {
+        if self.compacted_since_usage {
+            let compacted = self.last_compaction.as_ref()?.post_tokens;
+            return compacted.or(self.last_usage);
+        }
+        self.last_usage
+    }
+
+    /// `context_tokens` is an estimate of what a compaction left.
+    pub fn context_estimated(&self) -> bool {
+        self.compacted_since_usage
+            && self
+                .last_compaction
+                .as_ref()
+                .is_some_and(|compaction| compaction.estimated)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    inode: u64,
+    /// Bytes read so far, always at a line boundary.
+    size: u64,
+    /// Unix time of the last read that advanced `size`.
+    #[serde(default)]
+    read_at: u64,
+    #[serde(flatten)]
+    state: TailState,
+}
+
+/// The transcript of the current session. It is read lazily and at most
+/// once, so segments can share it freely.
+pub struct Transcript {
+    path: PathBuf,
+    tail: OnceCell<Option<TailState>>,
+}
+
+impl Transcript {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            tail: OnceCell::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Latest state, read incrementally through the index.
+    pub fn tail(&self) -> Option<&TailState> {
+        self.tail.get_or_init(|| tail(&self.path)).as_ref()
+    }
+}
+
+/// Latest state of the transcript at `path`, reading as little of it as the
+/// index allows.
+fn tail(path: &Path) -> Option<TailState> {
+    let metadata = fs::metadata(path).ok()?;
+    let inode = inode(&metadata);
+    let key = path.to_string_lossy().to_string();
+    let mut index = load_index();
+
+    let (mut state, size) = match index.get(&key) {
+        Some(entry) if entry.inode == inode && entry.size == metadata.len() => {
+            return Some(entry.state.clone());
+        }
+        Some(entry) if entry.inode == inode && entry.size < metadata.len() => {
+            read_appended(path, entry.size, entry.state.clone())?
+        }
+        _ => scan(path)?,
+    };
+    // The chain a summary points into is history that no longer changes, so
+    // the project index is only consulted for a new leaf
+    if let (Some(leaf), None) = (&state.summary_leaf, state.summary_usage) {
+        state.summary_usage = path
+            .parent()
+            .and_then(|dir| uuid_index::usage_at(dir, leaf));
+    }
+
+    index.insert(
+        key,
+        IndexEntry {
+            inode,
+            size,
+            read_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            state: state.clone(),
+        },
+    );
+    save_index(index);
+    Some(state)
+}
+
+/// First read of a file. Returns the state and the offset just past the last
+/// complete line.
+fn scan(path: &Path) -> Option<(TailState, u64)> {
+    let mut file = File::open(path).ok()?;
+    let start = last_usage_offset(&mut file)?;
+    let mut state = TailState::default();
+    replay_marked(&mut file, start, &mut state)?;
+    read_appended(path, start, state)
+}
+
+/// Offset of the last complete line holding main-thread usage, or 0.
+fn last_usage_offset(file: &mut File) -> Option<u64> {
+    let mut pos = file.metadata().ok()?.len();
+    let mut seen_newline = false;
+    // Start of a line whose beginning lies in an earlier block
+    let mut carry: Vec<u8> = Vec::new();
+
+    loop {
+        let start = pos.saturating_sub(BLOCK_SIZE);
+        let mut buf = vec![0; (pos - start) as usize];
+        file.seek(SeekFrom::Start(start)).ok()?;
+        file.read_exact(&mut buf).ok()?;
+        buf.extend_from_slice(&carry);
+
+        let mut end = buf.len();
+        for i in (0..buf.len()).rev() {
+            if buf[i] != b'\n' {
+                continue;
+            }
+            // Text after the last newline may still be in the middle of a write
+            if std::mem::replace(&mut seen_newline, true) && has_usage(&buf[i + 1..end]) {
+                return Some(start + i as u64 + 1);
+            }
+            end = i;
+        }
+        carry = buf[..end].to_vec();
+
+        if start == 0 {
+            return Some(0);
+        }
+        pos = start;
+    }
+}
+
+fn has_usage(line: &[u8]) -> bool {
+    serde_json::from_slice::<TranscriptEntry>(line)
+        .ok()
+        .and_then(|entry| usage_tokens(&entry))
+        .is_some()
+}
+
+/// Parse the bytes appended after `from` on top of the previous state.
+/// Returns the new state and the offset just past the last complete line.
+fn read_appended(path: &Path, from: u64, mut state: TailState) -> Option<(TailState, u64)> {
+    let mut file = File::open(path).ok()?;
+    file.seek(SeekFrom::Start(from)).ok()?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).ok()?;
+
+    // A trailing line without newline may still be in the middle of a write
+    let complete = buf.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let mut offset = from;
+    for line in buf[..complete].split(|&b| b == b'\n') {
+        let line_offset = offset;
+        offset += line.len() as u64 + 1;
+
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
+            apply(&mut state, &entry, line.as_bytes(), line_offset);
+        }
+    }
+
+    Some((state, from + complete as u64))
+}
+
+/// Apply the lines before `end` that the backward scan passed over but that
+/// still count: subagent turns, compactions and the first turn's usage. Every
+/// line is read, but only those carrying one of their markers are parsed as
+/// JSON.
+fn replay_marked(file: &mut File, end: u64, state: &mut TailState) -> Option<()> {
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let markers = [
+        memmem::Finder::new(b"\"isSidechain\":true"),
+        memmem::Finder::new(b"\"subtype\":\"compact_boundary\""),
+        memmem::Finder::new(b"\"isCompactSummary\":true"),
+    ];
+    let usage = memmem::Finder::new(b"\"usage\":");
+    let mut reader = BufReader::new(file.take(end));
+    let mut line = Vec::new();
+    let mut offset = 0;
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).ok()?;
+        if read == 0 {
+            return Some(());
+        }
+        let line_offset = offset;
+        offset += read as u64;
+
+        let marked = markers.iter().any(|marker| marker.find(&line).is_some())
+            || (state.prefix_tokens.is_none() && usage.find(&line).is_some());
+        if !marked {
+            continue;
+        }
+        if let Ok(entry) = serde_json::from_slice::<TranscriptEntry>(&line) {
+            apply(state, &entry, &line, line_offset);
+        }
+    }
+}
+
+/// Update the state with the entry parsed from `line` at `offset`, in file
+/// order.
+fn apply(state: &mut TailState, entry: &TranscriptEntry, line: &[u8], offset: u64) {
+    if entry.is_sidechain {
+        add_subagent_turn(state, entry);
+        return;
+    }
+
+    let leaf = if entry.r#type.as_deref() == Some("summary") {
+        entry.leaf_uuid.clone()
+    } else {
+        None
+    };
+    if leaf != state.summary_leaf {
+        state.summary_usage = None;
+    }
+    state.summary_leaf = leaf;
+
+    if entry.subtype.as_deref() == Some("compact_boundary") {
+        let metadata = entry.compact_metadata.as_ref();
+        state.compactions += 1;
+        state.last_compaction = Some(Compaction {
+            pre_tokens: metadata.and_then(|m| m.pre_tokens),
+            post_tokens: metadata.and_then(|m| m.post_tokens),
+            estimated: false,
+        });
+        state.compacted_since_usage = true;
+    } else if entry.is_compact_summary && state.compacted_since_usage {
+        // Without a recorded size, what is left is the fixed prefix plus the
+        // summary, at ~4 characters per token
+        if let Some(compaction) = &mut state.last_compaction {
+            if compaction.post_tokens.is_none() {
+                let chars = serde_json::from_slice::<SummaryLine>(line)
+                    .ok()
+                    .and_then(|summary| summary.message?.content)
+                    .map_or(0, |content| content_chars(&content));
+                let prefix = state.prefix_tokens.unwrap_or(0);
+                compaction.post_tokens = Some(prefix + (chars / 4) as u32);
+                compaction.estimated = true;
+            }
+        }
+    }
+
+    if let Some(tokens) = usage_tokens(entry) {
+        if state.prefix_tokens.is_none() {
+            let usage = entry.message.as_ref().and_then(|m| m.usage.as_ref());
+            state.prefix_tokens = usage.map(|usage| {
+                let usage = usage.clone().normalize();
+                usage.cache_creation_input_tokens + usage.cache_read_input_tokens
+            });
+        }
+        state.last_usage = Some(tokens);
+        state.last_offset = Some(offset);
+        state.compacted_since_usage = false;
+    }
+}
+
+/// The message text of a compaction summary. Parsed only for that entry, so
+/// no other line has its content built into JSON values.
+#[derive(Deserialize)]
+struct SummaryLine {
+    message: Option<SummaryMessage>,
+}
+
+#[derive(Deserialize)]
+struct SummaryMessage {
+    content: Option<serde_json::Value>,
+}
+
+/// Characters of text in message content, plain or as text blocks.
+fn content_chars(content: &serde_json::Value) -> usize {
+    match content {
+        serde_json::Value::String(text) => text.chars().count(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|text| text.as_str()))
+            .map(|text| text.chars().count())
+            .sum(),
+        _ => 0,
+    }
+}
+
+fn add_subagent_turn(state: &mut TailState, entry: &TranscriptEntry) {
+    if entry.r#type.as_deref() != Some("assistant") {
+        return;
+    }
+    if let Some(usage) = entry.message.as_ref().and_then(|m| m.usage.as_ref()) {
+        state.subagent_tokens += u64::from(usage.clone().normalize().total_for_cost());
+        state.subagent_turns += 1;
+    }
+}
+
+/// Context tokens reported by a main-thread assistant entry.
+pub(crate) fn usage_tokens(entry: &TranscriptEntry) -> Option<u32> {
+    if entry.r#type.as_deref() != Some("assistant") || entry.is_sidechain {
+        return None;
+    }
+    let usage = entry.message.as_ref()?.usage.as_ref()?;
+    Some(usage.clone().normalize().display_tokens())
+}
+
+#[cfg(unix)]
+pub(crate) fn inode(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+pub(crate) fn inode(_metadata: &Metadata) -> u64 {
+    0
+}
+
+fn index_path() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    Some(
+        home.join(".claude")
+            .join("eflowcodeline")
+            .join("cache")
+            .join("transcript_index.json"),
+    )
+}
+
+fn load_index() -> HashMap<String, IndexEntry> {
+    index_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Best effort, like every other cache: a failed write only costs a rescan.
+fn save_index(mut index: HashMap<String, IndexEntry>) {
+    let Some(path) = index_path() else {
+        return;
+    };
+    evict_stale(&mut index);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(raw) = serde_json::to_string(&index) {
+        let _ = write_atomic(&path, raw.as_bytes());
+    }
+}
+
+/// Keep the `MAX_INDEXED` most recently read transcripts. Deleted ones stop
+/// being read and age out.
+fn evict_stale(index: &mut HashMap<String, IndexEntry>) {
+    if index.len() <= MAX_INDEXED {
+        return;
+    }
+    let mut entries: Vec<_> = index.drain().collect();
+    entries.sort_unstable_by_key(|(_, entry)| std::cmp::Reverse(entry.read_at));
+    entries.truncate(MAX_INDEXED);
+    index.extend(entries);
+}
+
+/// Replace `path` by writing a sibling file and renaming it over, so
+/// concurrent statusline runs never read a half-written cache.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+    let result = fs::write(&tmp, contents).and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// A transcript file, removed along with the returned directory.
+    fn transcript_file(content: &str) -> (TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    fn assistant(tokens: u32) -> String {
+        format!(
+            r#"{{"type":"assistant","uuid":"a{0}","message":{{"usage":{{"input_tokens":{0},"output_tokens":0}}}}}}"#,
+            tokens
+        )
+    }
+
+    #[test]
+    fn backward_scan_and_appends_agree_with_a_full_read() {
+        // Long enough to span several blocks
+        let padding = format!(r#"{{"type":"user","text":"{}"}}"#, "x".repeat(100_000));
+        let mut content = format!("{}\n{}\n{}\n", assistant(100), padding, assistant(200));
+        content.push_str(&padding);
+        content.push('\n');
+        let (_dir, path) = transcript_file(&content);
+
+        let (state, size) = scan(&path).unwrap();
+        assert_eq!(state.last_usage, Some(200));
+        assert_eq!(state.summary_leaf, None);
+        assert_eq!(size, content.len() as u64);
+        let offset = state.last_offset.unwrap() as usize;
+        assert!(content[offset..].starts_with(&assistant(200)));
+
+        // A complete line and one still being written
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{}\n{{\"type\":\"summ", assistant(300)).unwrap();
+        let (state, new_size) = read_appended(&path, size, state).unwrap();
+        assert_eq!(state.last_usage, Some(300));
+        assert_eq!(state.last_offset, Some(size));
+
+        writeln!(file, "ary\",\"leafUuid\":\"a300\"}}").unwrap();
+        let (state, _) = read_appended(&path, new_size, state).unwrap();
+        assert_eq!(state.summary_leaf.as_deref(), Some("a300"));
+        assert_eq!(scan(&path).unwrap().0, state);
+
+        // A resolved summary is forgotten once the transcript moves on
+        let mut state = state;
+        state.summary_usage = Some(42);
+        let size = fs::metadata(&path).unwrap().len();
+        writeln!(file, "{}", assistant(400)).unwrap();
+        let (state, _) = read_appended(&path, size, state).unwrap();
+        assert_eq!((state.summary_leaf, state.summary_usage), (None, None));
+    }
+
+    #[test]
+    fn sidechain_turns_are_counted_apart_from_the_main_thread() {
+        let sidechain = |tokens: u32| {
+            assistant(tokens).replace(
+                r#""type":"assistant""#,
+                r#""type":"assistant","isSidechain":true,"agentId":"x""#,
+            )
+        };
+        let content = format!(
+            "{}\n{}\n{}\n",
+            sidechain(40),
+            assistant(100),
+            sidechain(500)
+        );
+        let (_dir, path) = transcript_file(&content);
+
+        let (state, size) = scan(&path).unwrap();
+        assert_eq!(state.last_usage, Some(100));
+        assert_eq!((state.subagent_turns, state.subagent_tokens), (2, 540));
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{}", sidechain(7)).unwrap();
+        let (state, _) = read_appended(&path, size, state).unwrap();
+        assert_eq!(state.last_usage, Some(100));
+        assert_eq!((state.subagent_turns, state.subagent_tokens), (3, 547));
+    }
+
+    #[test]
+    fn compactions_replace_stale_usage_until_the_next_turn() {
+        let boundary = |pre: u32, post: &str| {
+            format!(
+                r#"{{"type":"system","subtype":"compact_boundary","compactMetadata":{{"trigger":"auto","preTokens":{}{}}}}}"#,
+                pre, post
+            )
+        };
+        let first_turn = r#"{"type":"assistant","message":{"usage":{"input_tokens":10,"cache_creation_input_tokens":3000,"cache_read_input_tokens":2000,"output_tokens":0}}}"#;
+        let content = [
+            boundary(150_000, r#","postTokens":10000"#),
+            first_turn.to_string(),
+            assistant(160_000),
+            boundary(160_000, ""),
+            format!(
+                r#"{{"type":"user","isCompactSummary":true,"message":{{"content":"{}"}}}}"#,
+                "s".repeat(4_000)
+            ),
+        ]
+        .join("\n")
+            + "\n";
+        let (_dir, path) = transcript_file(&content);
+
+        let (state, size) = scan(&path).unwrap();
+        assert_eq!(state.last_usage, Some(160_000));
+        assert_eq!(state.compactions, 2);
+        assert!(state.compacted_since_usage);
+        assert_eq!(state.prefix_tokens, Some(5_000));
+        // The prefix plus 4,000 characters of summary
+        let compaction = state.last_compaction.clone().unwrap();
+        assert_eq!(compaction.post_tokens, Some(6_000));
+        assert_eq!(compaction.reclaimed_tokens(), Some(154_000));
+        assert_eq!(state.context_tokens(), Some(6_000));
+        assert!(state.context_estimated());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{}", assistant(3_000)).unwrap();
+        let (state, _) = read_appended(&path, size, state).unwrap();
+        assert_eq!(state.context_tokens(), Some(3_000));
+        assert!(!state.context_estimated());
+        assert_eq!(state.compactions, 2);
+        assert_eq!(scan(&path).unwrap().0, state);
+    }
+
+    #[test]
+    fn index_keeps_the_most_recently_read_transcripts() {
+        let mut index: HashMap<String, IndexEntry> = (0..MAX_INDEXED as u64 + 5)
+            .map(|i| {
+                let entry = IndexEntry {
+                    inode: i,
+                    size: 0,
+                    read_at: i,
+                    state: TailState::default(),
+                };
+                (format!("/t/{}.jsonl", i), entry)
+            })
+            .collect();
+        evict_stale(&mut index);
+        assert_eq!(index.len(), MAX_INDEXED);
+        assert!(!index.contains_key("/t/4.jsonl"));
+        assert!(index.contains_key("/t/5.jsonl"));
+    }
+}
@@ -0,0 +1,284 @@
+// Where each message UUID of a project lives.
+//
+// A transcript that ends in a summary points at the last message of an earlier
+// session through `leafUuid`, which may sit in any of the project's session
+// files. Rather than scanning all of them, every project directory gets a
+// cached index of uuid -> (file, byte offset):
+//
+//   ~/.claude/eflowcodeline/cache/uuid_index_<project dir name>.json
+//
+// A session file that grew is indexed from where the last run stopped, one
+// that was replaced or shrank from the start. A lookup is then a single seek
+// per message on the chain. The transcript index remembers what a summary
+// resolved to, so this index is only loaded when the transcript changed.
+
+use crate::config::TranscriptEntry;
+use crate::core::transcript::{inode, usage_tokens, write_atomic};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProjectIndex {
+    /// Keyed by session file name.
+    files: HashMap<String, IndexedFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedFile {
+    inode: u64,
+    mtime_ns: u64,
+    /// File length when last indexed.
+    len: u64,
+    /// Bytes indexed so far, always at a line boundary.
+    indexed: u64,
+    uuids: HashMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+struct UuidOnly {
+    uuid: Option<String>,
+}
+
+/// Context tokens at message `uuid` of any session in `project_dir`: the usage
+/// of the nearest assistant message on its chain.
+pub fn usage_at(project_dir: &Path, uuid: &str) -> Option<u32> {
+    let cache = cache_path(project_dir);
+    let mut index = cache
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<ProjectIndex>(&raw).ok())
+        .unwrap_or_default();
+
+    if index.refresh(project_dir) {
+        if let Some(path) = &cache {
+            // Best effort: a failed write only costs a re-index
+            if let Ok(raw) = serde_json::to_string(&index) {
+                let _ = write_atomic(path, raw.as_bytes());
+            }
+        }
+    }
+    index.usage_at(project_dir, uuid)
+}
+
+impl ProjectIndex {
+    /// Re-index changed and new session files, forget deleted ones. Returns
+    /// whether anything changed.
+    fn refresh(&mut self, project_dir: &Path) -> bool {
+        let Ok(dir) = fs::read_dir(project_dir) else {
+            return false;
+        };
+        let mut changed = false;
+        let mut present = HashSet::new();
+
+        for entry in dir.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let (Some(name), Ok(metadata)) = (file_name(&path), entry.metadata()) else {
+                continue;
+            };
+            let inode = inode(&metadata);
+            let mtime_ns = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_nanos() as u64);
+            let len = metadata.len();
+            present.insert(name.clone());
+
+            let file = self.files.entry(name).or_insert_with(|| IndexedFile {
+                inode,
+                mtime_ns: 0,
+                len: 0,
+                indexed: 0,
+                uuids: HashMap::new(),
+            });
+            if file.inode == inode && file.mtime_ns == mtime_ns && file.len == len {
+                continue;
+            }
+            // Replaced or truncated files start over; grown ones only need
+            // their new lines indexed
+            if file.inode != inode || len < file.indexed {
+                file.inode = inode;
+                file.indexed = 0;
+                file.uuids.clear();
+            }
+            if let Some(indexed) = index_from(&path, file.indexed, &mut file.uuids) {
+                file.indexed = indexed;
+            }
+            file.mtime_ns = mtime_ns;
+            file.len = len;
+            changed = true;
+        }
+
+        let before = self.files.len();
+        self.files.retain(|name, _| present.contains(name));
+        changed || self.files.len() != before
+    }
+
+    fn usage_at(&self, project_dir: &Path, uuid: &str) -> Option<u32> {
+        let mut visited = HashSet::new();
+        let mut uuid = uuid.to_string();
+        loop {
+            // Guards against parent cycles in damaged transcripts
+            if !visited.insert(uuid.clone()) {
+                return None;
+            }
+            let entry = self.entry(project_dir, &uuid)?;
+            if let Some(tokens) = usage_tokens(&entry) {
+                return Some(tokens);
+            }
+            uuid = entry.parent_uuid?;
+        }
+    }
+
+    /// The line holding `uuid`. Resumed sessions copy earlier history, so
+    /// the same message can be in several files; the newest one is used.
+    fn entry(&self, project_dir: &Path, uuid: &str) -> Option<TranscriptEntry> {
+        let (_, name, offset) = self
+            .files
+            .iter()
+            .filter_map(|(name, file)| Some((file.mtime_ns, name, *file.uuids.get(uuid)?)))
+            .max()?;
+        let mut file = File::open(project_dir.join(name)).ok()?;
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut line = String::new();
+        BufReader::new(file).read_line(&mut line).ok()?;
+        serde_json::from_str(line.trim()).ok()
+    }
+}
+
+/// Add the offset of every complete line from `from` on that carries a
+/// `uuid`. Returns the offset just past the last complete line.
+fn index_from(path: &Path, from: u64, uuids: &mut HashMap<String, u64>) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    file.seek(SeekFrom::Start(from)).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    let mut offset = from;
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).ok()?;
+        // A trailing line without newline may still be in the middle of a write
+        if read == 0 || line.last() != Some(&b'\n') {
+            return Some(offset);
+        }
+        if let Ok(UuidOnly { uuid: Some(uuid) }) = serde_json::from_slice(&line) {
+            uuids.insert(uuid, offset);
+        }
+        offset += read as u64;
+    }
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    Some(path.file_name()?.to_str()?.to_string())
+}
+
+fn cache_path(project_dir: &Path) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let cache_dir = home.join(".claude").join("eflowcodeline").join("cache");
+    fs::create_dir_all(&cache_dir).ok()?;
+    Some(cache_dir.join(format!("uuid_index_{}.json", file_name(project_dir)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_resolve_across_session_files_and_follow_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::write(
+            dir.join("old.jsonl"),
+            concat!(
+                "{\"type\":\"user\",\"uuid\":\"u0\"}\n",
+                "{\"type\":\"assistant\",\"uuid\":\"a1\",\"parentUuid\":\"u0\",",
+                "\"message\":{\"usage\":{\"input_tokens\":700,\"output_tokens\":0}}}\n",
+            ),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("new.jsonl"),
+            "{\"type\":\"user\",\"uuid\":\"u1\",\"parentUuid\":\"a1\"}\n",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "{\"uuid\":\"x\"}\n").unwrap();
+
+        let mut index = ProjectIndex::default();
+        assert!(index.refresh(dir));
+        assert!(!index.refresh(dir));
+        assert_eq!(index.files.len(), 2);
+        assert_eq!(index.usage_at(dir, "u1"), Some(700));
+        assert_eq!(index.usage_at(dir, "u0"), None);
+        assert_eq!(index.usage_at(dir, "x"), None);
+
+        fs::write(
+            dir.join("old.jsonl"),
+            "{\"type\":\"assistant\",\"uuid\":\"a1\",\"message\":{\"usage\":{\"input_tokens\":900,\"output_tokens\":0}}}\n",
+        )
+        .unwrap();
+        fs::remove_file(dir.join("new.jsonl")).unwrap();
+        assert!(index.refresh(dir));
+        assert_eq!(index.files.len(), 1);
+        assert_eq!(index.usage_at(dir, "a1"), Some(900));
+    }
+
+    #[test]
+    fn appends_are_indexed_incrementally_and_copies_resolve_to_the_newest_file() {
+        use std::io::Write;
+        use std::time::{Duration, SystemTime};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let user = "{\"type\":\"user\",\"uuid\":\"u0\"}\n";
+        let assistant = |tokens: u32| {
+            format!(
+                "{{\"type\":\"assistant\",\"uuid\":\"a1\",\"message\":{{\"usage\":{{\"input_tokens\":{},\"output_tokens\":0}}}}}}\n",
+                tokens
+            )
+        };
+        let line = assistant(700);
+        let (head, rest) = line.split_at(20);
+        fs::write(dir.join("s1.jsonl"), format!("{}{}", user, head)).unwrap();
+
+        // The unfinished line is left for the next refresh
+        let mut index = ProjectIndex::default();
+        assert!(index.refresh(dir));
+        assert_eq!(index.files["s1.jsonl"].indexed, user.len() as u64);
+        assert_eq!(index.usage_at(dir, "a1"), None);
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(dir.join("s1.jsonl"))
+            .unwrap();
+        file.write_all(rest.as_bytes()).unwrap();
+        assert!(index.refresh(dir));
+        assert_eq!(index.files["s1.jsonl"].uuids["a1"], user.len() as u64);
+        assert_eq!(index.usage_at(dir, "a1"), Some(700));
+
+        // A resumed session holding a copy of the same message
+        fs::write(dir.join("s2.jsonl"), assistant(900)).unwrap();
+        let touch = |name: &str, secs: u64| {
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            File::options()
+                .write(true)
+                .open(dir.join(name))
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+        touch("s1.jsonl", 1_000);
+        touch("s2.jsonl", 2_000);
+        index.refresh(dir);
+        assert_eq!(index.usage_at(dir, "a1"), Some(900));
+        touch("s1.jsonl", 3_000);
+        index.refresh(dir);
+        assert_eq!(index.usage_at(dir, "a1"), Some(700));
+    }
+}
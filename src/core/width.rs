@@ -0,0 +1,101 @@
+// Display width of rendered statusline text.
+//
+// Everything that measures rendered output (row fitting, wrapping, alignment
+// and the TUI) goes through `visible_width`, so the terminal statusline and the
+// preview always break lines in the same places.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+/// Remove every escape sequence, keeping only the visible text: CSI (colors),
+/// OSC (hyperlinks), DCS and the other ST-terminated strings.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte such as `m`
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC, DCS, SOS, PM, APC: up to BEL or ESC \
+            Some(']' | 'P' | 'X' | '^' | '_') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Terminal columns taken by `text`, ignoring escape sequences.
+pub fn visible_width(text: &str) -> usize {
+    strip_ansi(text).graphemes(true).map(grapheme_width).sum()
+}
+
+/// Width of one grapheme cluster. Only the base character counts, so combining
+/// marks and ZWJ emoji sequences take the width of a single glyph.
+fn grapheme_width(grapheme: &str) -> usize {
+    let Some(base) = grapheme.chars().next() else {
+        return 0;
+    };
+    let width = base.width().unwrap_or(0);
+    if width == 0 {
+        return 0;
+    }
+
+    // Variation selectors pick emoji (wide) or text (narrow) presentation
+    if grapheme.contains('\u{fe0f}') {
+        2
+    } else if grapheme.contains('\u{fe0e}') {
+        1
+    } else if ('\u{1f1e6}'..='\u{1f1ff}').contains(&base) {
+        // A pair of regional indicators forms one flag
+        2
+    } else {
+        width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emoji_sequences_take_one_glyph() {
+        assert_eq!(visible_width("⚡️ 42%"), 6);
+        assert_eq!(visible_width("☀️"), 2);
+        assert_eq!(visible_width("👨‍👩‍👧"), 2);
+        assert_eq!(visible_width("🇨🇳"), 2);
+        assert_eq!(visible_width("e\u{301}中文"), 5);
+    }
+
+    #[test]
+    fn escape_sequences_are_skipped() {
+        assert_eq!(visible_width("\x1b[1;38;2;1;2;3mab\x1b[0m"), 2);
+        assert_eq!(
+            visible_width("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"),
+            4
+        );
+        assert_eq!(visible_width("\x1b]8;;file:///tmp\x07dir\x1b]8;;\x07"), 3);
+        assert_eq!(visible_width("\x1bP1$r0m\x1b\\ok"), 2);
+    }
+}
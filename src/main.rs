@@ -1,7 +1,9 @@
-use eflowcodeline::cli::Cli;
-use eflowcodeline::config::{Config, InputData};
+use eflowcodeline::cli::{Cli, OutputFormat};
+use eflowcodeline::config::{last_session, ColorDepth, Config, InputData};
+use eflowcodeline::core::json::render_json;
+use eflowcodeline::core::targets::RenderTarget;
 use eflowcodeline::core::{collect_all_segments, StatusLineGenerator};
-use std::io::{self, IsTerminal};
+use std::io::{self, IsTerminal, Read};
 
 /// Detect terminal width even when stdout/stdin are piped.
 /// On Windows, opens CONOUT$ directly; on Unix, opens /dev/tty.
@@ -121,18 +123,60 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     if cli.print {
-        let config = Config::load().unwrap_or_else(|_| Config::default());
+        use eflowcodeline::config::ConfigLoader;
+        let cwd = std::env::current_dir().ok();
+        let (config, report) = ConfigLoader::load_for_dir(cwd.as_deref())?;
         config.print()?;
+        report.print_sources();
         return Ok(());
     }
 
     if cli.check {
-        let config = Config::load()?;
+        use eflowcodeline::config::ConfigLoader;
+        let cwd = std::env::current_dir().ok();
+        let (config, report) = ConfigLoader::load_for_dir(cwd.as_deref())?;
+        report.print();
         config.check()?;
         println!("✓ Configuration valid");
         return Ok(());
     }
 
+    if let Some(path) = cli.import_theme {
+        use eflowcodeline::config::import::import_theme;
+        use eflowcodeline::ui::themes::ThemePresets;
+
+        let result = import_theme(&path)?;
+
+        // Never overwrite a built-in preset or an existing theme file
+        let themes = ThemePresets::list_themes();
+        let mut name = result.theme_name.clone();
+        let mut n = 2;
+        while themes.contains(&name) {
+            name = format!("{}-{}", result.theme_name, n);
+            n += 1;
+        }
+
+        let saved = ThemePresets::save_theme(&name, &result.config)?;
+        println!(
+            "Imported {} config {} as theme \"{}\"",
+            result.format.name(),
+            path.display(),
+            name
+        );
+        println!("Saved to {}", saved.display());
+        if !result.warnings.is_empty() {
+            println!("\nNot translated:");
+            for warning in &result.warnings {
+                println!("  - {}", warning);
+            }
+        }
+        println!(
+            "\nActivate with `theme = \"{}\"` in config.toml or from the TUI.",
+            name
+        );
+        return Ok(());
+    }
+
     if cli.config {
         #[cfg(feature = "tui")]
         {
@@ -184,19 +228,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Load configuration (single hard-coded theme)
-    let config = Config::load().unwrap_or_else(|_| Config::default());
-
     // Check if stdin has data
-    if io::stdin().is_terminal() {
+    if !cli.last_session && io::stdin().is_terminal() {
         // Check if this is first-time run (no config exists)
         #[cfg(feature = "tui")]
         {
-            use std::path::PathBuf;
-
-            // Try to get config path (使用与 Config::get_config_path() 相同的路径)
-            let config_path: Option<PathBuf> = dirs::home_dir()
-                .map(|p| p.join(".claude").join("eflowcodeline").join("config.toml"));
+            let config_path = Config::get_config_path();
 
             let is_first_run = config_path.as_ref().map(|p| !p.exists()).unwrap_or(false);
 
@@ -232,13 +269,45 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Read Claude Code data from stdin
-    let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    // Read Claude Code data from stdin, or reuse what it sent last time
+    let input: InputData = if cli.last_session {
+        last_session::load()?
+    } else {
+        let mut raw = String::new();
+        io::stdin().read_to_string(&mut raw)?;
+        let input = serde_json::from_str(&raw)?;
+        last_session::save(&raw);
+        input
+    };
+
+    // Load configuration: built-in theme + user config.toml + project .eflowcodeline.toml
+    let mut config = Config::load_for_workspace(&input.workspace.current_dir)
+        .unwrap_or_else(|_| Config::default());
+    if cli.no_color {
+        config.style.color_depth = ColorDepth::None;
+    }
 
     // Collect segment data
     let segments_data = collect_all_segments(&config, &input);
 
+    let target = match cli.format {
+        OutputFormat::Json => {
+            println!("{}", render_json(&segments_data));
+            return Ok(());
+        }
+        OutputFormat::Ansi => None,
+        OutputFormat::Tmux => Some(RenderTarget::Tmux),
+        OutputFormat::Zsh => Some(RenderTarget::Zsh),
+        OutputFormat::Bash => Some(RenderTarget::Bash),
+    };
+
+    // tmux and shell prompts manage their own width, so rows are not wrapped
+    if let Some(target) = target {
+        let generator = StatusLineGenerator::new(config).with_target(target);
+        println!("{}", generator.generate(segments_data));
+        return Ok(());
+    }
+
     // Render statusline with terminal-width-aware wrapping
     let generator = StatusLineGenerator::new(config);
     let terminal_width = detect_terminal_width();
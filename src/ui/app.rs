@@ -1,4 +1,5 @@
-use crate::config::{Config, SegmentId, StyleMode};
+use crate::config::{Align, Config, SegmentId, StyleMode};
+use crate::core::width::visible_width;
 use crate::ui::components::{
     color_picker::{ColorPickerComponent, NavDirection},
     help::HelpComponent,
@@ -8,8 +9,11 @@ use crate::ui::components::{
     segment_list::{FieldSelection, Panel, SegmentListComponent},
     separator_editor::SeparatorEditorComponent,
     settings::SettingsComponent,
+    text_style_editor::TextStyleEditorComponent,
     theme_selector::ThemeSelectorComponent,
+    threshold_editor::{ThresholdColorTarget, ThresholdEditorComponent},
 };
+use crate::ui::themes::{ThemePresets, BUILTIN_THEMES};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
@@ -38,6 +42,8 @@ pub struct App {
     separator_editor: SeparatorEditorComponent,
     settings: SettingsComponent,
     theme_selector: ThemeSelectorComponent,
+    threshold_editor: ThresholdEditorComponent,
+    text_style_editor: TextStyleEditorComponent,
     help: HelpComponent,
     status_message: Option<String>,
 }
@@ -58,6 +64,8 @@ impl App {
             separator_editor: SeparatorEditorComponent::new(),
             settings: SettingsComponent::new(),
             theme_selector: ThemeSelectorComponent::new(),
+            threshold_editor: ThresholdEditorComponent::new(),
+            text_style_editor: TextStyleEditorComponent::new(),
             help: HelpComponent::new(),
             status_message: None,
         };
@@ -66,8 +74,8 @@ impl App {
     }
 
     pub fn run() -> Result<(), Box<dyn std::error::Error>> {
-        // Themes are hard-coded; just take the default config in TUI mode.
-        let config = Config::default();
+        // Built-in theme with the user's config.toml layered on top.
+        let config = Config::load().unwrap_or_default();
 
         // Terminal setup
         enable_raw_mode()?;
@@ -169,6 +177,10 @@ impl App {
                         }
                         _ => {}
                     }
+                } else if app.threshold_editor.is_open {
+                    app.handle_threshold_key(key.code);
+                } else if app.text_style_editor.is_open {
+                    app.handle_text_style_key(key.code);
                 } else {
                     // Handle main app events
                     match key.code {
@@ -208,10 +220,12 @@ impl App {
                         }
                         KeyCode::Enter => app.toggle_current(),
                         KeyCode::Tab => app.switch_panel(),
-                        KeyCode::Char('1') => app.switch_to_theme("default"),
-                        KeyCode::Char('2') => app.switch_to_theme("minimal"),
-                        KeyCode::Char('3') => app.switch_to_theme("gruvbox"),
-                        KeyCode::Char('4') => app.switch_to_theme("nord"),
+                        KeyCode::Char(c @ '1'..='4') => {
+                            let index = c as usize - '1' as usize;
+                            if let Some(name) = BUILTIN_THEMES.get(index) {
+                                app.switch_to_theme(name);
+                            }
+                        }
                         KeyCode::Char('p') => app.cycle_theme(),
                         KeyCode::Char('r') => app.reset_to_theme_defaults(),
                         KeyCode::Char('e') | KeyCode::Char('E') => app.open_separator_editor(),
@@ -233,9 +247,8 @@ impl App {
         result
     }
 
-    fn calculate_theme_selector_height(&self, _total_width: u16) -> u16 {
-        // Theme is locked: name + separator = 2 lines + borders.
-        4
+    fn calculate_theme_selector_height(&self, total_width: u16) -> u16 {
+        self.theme_selector.required_height(total_width)
     }
 
     fn calculate_help_height(&self, total_width: u16) -> u16 {
@@ -277,7 +290,7 @@ impl App {
 
         // Use same logic as help.render for line wrapping
         for (i, item) in help_items.iter().enumerate() {
-            let item_width = item.chars().count();
+            let item_width = visible_width(item);
             let needs_separator = i > 0 && current_width > 0;
             let separator_width = if needs_separator { 2 } else { 0 };
             let total_width = item_width + separator_width;
@@ -395,6 +408,16 @@ impl App {
         );
 
         // Render popups on top
+        if self.threshold_editor.is_open {
+            if let Some(segment) = self.config.segments.get(self.selected_segment) {
+                self.threshold_editor.render(f, f.area(), segment);
+            }
+        }
+        if self.text_style_editor.is_open {
+            if let Some(segment) = self.config.segments.get(self.selected_segment) {
+                self.text_style_editor.render(f, f.area(), segment);
+            }
+        }
         if self.color_picker.is_open {
             self.color_picker.render(f, f.area());
         }
@@ -419,7 +442,7 @@ impl App {
                 self.selected_segment = new_selection;
             }
             Panel::Settings => {
-                let field_count = 7; // Enabled, Icon, IconColor, TextColor, TextStyle, BackgroundColor, Options
+                let field_count = 9; // Enabled, Icon, IconColor, TextColor, BackgroundColor, TextStyle, Align, Thresholds, Options
                 let current_field = match self.selected_field {
                     FieldSelection::Enabled => 0i32,
                     FieldSelection::Icon => 1,
@@ -427,7 +450,9 @@ impl App {
                     FieldSelection::TextColor => 3,
                     FieldSelection::BackgroundColor => 4,
                     FieldSelection::TextStyle => 5,
-                    FieldSelection::Options => 6,
+                    FieldSelection::Align => 6,
+                    FieldSelection::Thresholds => 7,
+                    FieldSelection::Options => 8,
                 };
                 let new_field = (current_field + delta).clamp(0, field_count - 1) as usize;
                 self.selected_field = match new_field {
@@ -437,7 +462,9 @@ impl App {
                     3 => FieldSelection::TextColor,
                     4 => FieldSelection::BackgroundColor,
                     5 => FieldSelection::TextStyle,
-                    6 => FieldSelection::Options,
+                    6 => FieldSelection::Align,
+                    7 => FieldSelection::Thresholds,
+                    8 => FieldSelection::Options,
                     _ => FieldSelection::Enabled,
                 };
             }
@@ -509,21 +536,20 @@ impl App {
                     FieldSelection::IconColor
                     | FieldSelection::TextColor
                     | FieldSelection::BackgroundColor => self.open_color_picker(),
-                    FieldSelection::TextStyle => {
-                        // Toggle text bold style
+                    FieldSelection::TextStyle => self.text_style_editor.open(),
+                    FieldSelection::Align => {
+                        // Cycle left -> center -> right
                         if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
-                            segment.styles.text_bold = !segment.styles.text_bold;
-                            self.status_message = Some(format!(
-                                "Text bold {}",
-                                if segment.styles.text_bold {
-                                    "enabled"
-                                } else {
-                                    "disabled"
-                                }
-                            ));
+                            segment.align = match segment.align {
+                                Align::Left => Align::Center,
+                                Align::Center => Align::Right,
+                                Align::Right => Align::Left,
+                            };
+                            self.status_message = Some(format!("Align {:?}", segment.align));
                             self.preview.update_preview(&self.config);
                         }
                     }
+                    FieldSelection::Thresholds => self.threshold_editor.open(),
                     FieldSelection::Options => {
                         // TODO: Implement options editor
                         self.status_message =
@@ -534,6 +560,60 @@ impl App {
         }
     }
 
+    fn handle_threshold_key(&mut self, code: KeyCode) {
+        let Some(segment) = self.config.segments.get_mut(self.selected_segment) else {
+            self.threshold_editor.close();
+            return;
+        };
+        let editor = &mut self.threshold_editor;
+        match code {
+            KeyCode::Esc => editor.close(),
+            KeyCode::Up => editor.move_selection(-1, segment),
+            KeyCode::Down => editor.move_selection(1, segment),
+            KeyCode::Left => editor.adjust_value(segment, -1.0),
+            KeyCode::Right => editor.adjust_value(segment, 1.0),
+            KeyCode::Tab => editor.toggle_comparison(segment),
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                if !editor.add_rule(segment) {
+                    self.status_message =
+                        Some("This segment has no numeric metric for thresholds".to_string());
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => editor.delete_rule(segment),
+            KeyCode::Char('b') | KeyCode::Char('B') if !segment.thresholds.is_empty() => {
+                editor.color_target = Some(ThresholdColorTarget::Background);
+                self.color_picker.open();
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') if !segment.thresholds.is_empty() => {
+                editor.color_target = Some(ThresholdColorTarget::Text);
+                self.color_picker.open();
+            }
+            _ => return,
+        }
+        self.preview.update_preview(&self.config);
+    }
+
+    fn handle_text_style_key(&mut self, code: KeyCode) {
+        let Some(segment) = self.config.segments.get_mut(self.selected_segment) else {
+            self.text_style_editor.close();
+            return;
+        };
+        let editor = &mut self.text_style_editor;
+        match code {
+            KeyCode::Esc => editor.close(),
+            KeyCode::Up => editor.move_selection(-1),
+            KeyCode::Down => editor.move_selection(1),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if editor.activate(segment) {
+                    self.color_picker.open();
+                }
+            }
+            KeyCode::Backspace | KeyCode::Delete => editor.clear_color(segment),
+            _ => return,
+        }
+        self.preview.update_preview(&self.config);
+    }
+
     fn switch_panel(&mut self) {
         self.selected_panel = match self.selected_panel {
             Panel::SegmentList => Panel::Settings,
@@ -559,6 +639,16 @@ impl App {
 
     fn apply_selected_color(&mut self, color: crate::config::AnsiColor) {
         if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
+            if self.threshold_editor.is_open {
+                self.threshold_editor.apply_color(segment, color);
+                self.preview.update_preview(&self.config);
+                return;
+            }
+            if self.text_style_editor.is_open {
+                self.text_style_editor.apply_color(segment, color);
+                self.preview.update_preview(&self.config);
+                return;
+            }
             match self.selected_field {
                 FieldSelection::IconColor => segment.colors.icon = Some(color),
                 FieldSelection::TextColor => segment.colors.text = Some(color),
@@ -580,12 +670,18 @@ impl App {
     }
 
     fn cycle_theme(&mut self) {
-        // Theme is locked.
-        self.status_message = Some("Theme switching is disabled.".to_string());
+        let themes = ThemePresets::list_themes();
+        let current = themes.iter().position(|t| *t == self.config.theme);
+        let next = match current {
+            Some(i) => (i + 1) % themes.len(),
+            None => 0,
+        };
+        let name = themes[next].clone();
+        self.switch_to_theme(&name);
     }
 
     fn switch_to_theme(&mut self, theme_name: &str) {
-        self.config = crate::ui::themes::ThemePresets::get_theme(theme_name);
+        self.config = ThemePresets::get_theme(theme_name);
         self.selected_segment = 0;
         self.preview.update_preview(&self.config);
         self.status_message = Some(format!("Switched to {} theme", theme_name));
@@ -594,7 +690,7 @@ impl App {
     /// Reset current theme to its default configuration
     fn reset_to_theme_defaults(&mut self) {
         let current_theme = self.config.theme.clone();
-        self.config = crate::ui::themes::ThemePresets::get_theme(&current_theme);
+        self.config = ThemePresets::get_theme(&current_theme);
         self.selected_segment = 0;
         self.preview.update_preview(&self.config);
         self.status_message = Some(format!("Reset {} theme to defaults", current_theme));
@@ -607,6 +703,9 @@ impl App {
 
     /// Move the currently selected segment up in the list
     fn move_segment_up(&mut self) {
+        if self.move_segment_in_rows(true) {
+            return;
+        }
         if self.selected_panel == Panel::SegmentList && self.selected_segment > 0 {
             let current_idx = self.selected_segment;
             self.config.segments.swap(current_idx, current_idx - 1);
@@ -618,6 +717,9 @@ impl App {
 
     /// Move the currently selected segment down in the list
     fn move_segment_down(&mut self) {
+        if self.move_segment_in_rows(false) {
+            return;
+        }
         if self.selected_panel == Panel::SegmentList
             && self.selected_segment < self.config.segments.len() - 1
         {
@@ -629,14 +731,53 @@ impl App {
         }
     }
 
-    /// Saving themes is disabled — themes are hard-coded.
+    /// With a row layout, reorder through `rows` so the preview follows the list.
+    fn move_segment_in_rows(&mut self, up: bool) -> bool {
+        if self.selected_panel != Panel::SegmentList {
+            return false;
+        }
+        let Some(id) = self
+            .config
+            .segments
+            .get(self.selected_segment)
+            .map(|s| s.id)
+        else {
+            return false;
+        };
+        let Some(row) = self.config.move_in_rows(id, up) else {
+            return false;
+        };
+
+        if let Some(index) = self.config.segments.iter().position(|s| s.id == id) {
+            self.selected_segment = index;
+        }
+        self.preview.update_preview(&self.config);
+        self.status_message = Some(format!("Moved segment (row {})", row + 1));
+        true
+    }
+
+    /// Write the current config to the active theme's file.
+    /// For a built-in theme this creates a theme file that shadows the preset.
     fn write_to_current_theme(&mut self) {
-        self.status_message = Some("Themes are hard-coded; saving is disabled.".to_string());
+        let theme_name = self.config.theme.clone();
+        self.status_message = Some(match ThemePresets::save_theme(&theme_name, &self.config) {
+            Ok(path) => format!("Theme {} written to {}", theme_name, path.display()),
+            Err(e) => format!("Failed to write theme: {}", e),
+        });
     }
 
-    /// Saving new themes is disabled — themes are hard-coded.
-    fn save_as_new_theme(&mut self, _theme_name: &str) {
-        self.status_message = Some("Themes are hard-coded; saving is disabled.".to_string());
+    /// Save the current config as a new theme file and switch to it.
+    fn save_as_new_theme(&mut self, theme_name: &str) {
+        match ThemePresets::save_theme(theme_name, &self.config) {
+            Ok(path) => {
+                self.config.theme = theme_name.to_string();
+                self.status_message =
+                    Some(format!("Theme {} saved to {}", theme_name, path.display()));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Failed to save theme: {}", e));
+            }
+        }
     }
 
     /// Open separator editor with current separator
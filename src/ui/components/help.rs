@@ -1,3 +1,4 @@
+use crate::core::width::visible_width;
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -63,7 +64,7 @@ impl HelpComponent {
 
         for (i, (key, description)) in help_items.iter().enumerate() {
             // Calculate item display width
-            let item_width = key.chars().count() + description.chars().count() + 1; // +1 for space
+            let item_width = visible_width(key) + visible_width(description) + 1; // +1 for space
 
             // Add separator for non-first items on the same line
             let needs_separator = i > 0 && !current_line_spans.is_empty();
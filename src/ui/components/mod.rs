@@ -7,4 +7,6 @@ pub mod preview;
 pub mod segment_list;
 pub mod separator_editor;
 pub mod settings;
+pub mod text_style_editor;
 pub mod theme_selector;
+pub mod threshold_editor;
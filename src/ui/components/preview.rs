@@ -3,7 +3,7 @@ use crate::core::segments::SegmentData;
 use crate::core::StatusLineGenerator;
 use ratatui::{
     layout::Rect,
-    text::{Line, Text},
+    text::Text,
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
@@ -45,23 +45,7 @@ impl PreviewComponent {
         // Generate TUI-optimized text with smart segment wrapping for preview display
         // Use actual available width minus borders
         let content_width = width.saturating_sub(2);
-        let preview_result = renderer.generate_for_tui_preview(segments_data, content_width);
-
-        // Convert to owned text by cloning the spans
-        let owned_lines: Vec<Line<'static>> = preview_result
-            .lines
-            .into_iter()
-            .map(|line| {
-                let owned_spans: Vec<ratatui::text::Span<'static>> = line
-                    .spans
-                    .into_iter()
-                    .map(|span| ratatui::text::Span::styled(span.content.to_string(), span.style))
-                    .collect();
-                Line::from(owned_spans)
-            })
-            .collect();
-
-        self.preview_text = Text::from(owned_lines);
+        self.preview_text = renderer.generate_for_tui_preview(segments_data, content_width);
     }
 
     pub fn calculate_height(&self) -> u16 {
@@ -101,6 +85,11 @@ impl PreviewComponent {
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
+                        map.insert(
+                            "model_id".to_string(),
+                            "claude-4-sonnet-20250512".to_string(),
+                        );
+                        map.insert("display_name".to_string(), "Sonnet 4".to_string());
                         map
                     },
                 },
@@ -110,6 +99,8 @@ impl PreviewComponent {
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("current_dir".to_string(), "~/EFlowCodeLine".to_string());
+                        map.insert("full_path".to_string(), "~/EFlowCodeLine".to_string());
+                        map.insert("dir_name".to_string(), "EFlowCodeLine".to_string());
                         map
                     },
                 },
@@ -122,6 +113,8 @@ impl PreviewComponent {
                         map.insert("status".to_string(), "Clean".to_string());
                         map.insert("ahead".to_string(), "0".to_string());
                         map.insert("behind".to_string(), "0".to_string());
+                        map.insert("status_icon".to_string(), "✓".to_string());
+                        map.insert("compact".to_string(), "master".to_string());
                         map
                     },
                 },
@@ -133,13 +126,26 @@ impl PreviewComponent {
                         map.insert("total_tokens".to_string(), "156400".to_string());
                         map.insert("percentage".to_string(), "78.2".to_string());
                         map.insert("session_tokens".to_string(), "48200".to_string());
+                        map.insert("tokens".to_string(), "156400".to_string());
+                        map.insert("tokens_k".to_string(), "156.4k".to_string());
+                        map.insert("percentage_display".to_string(), "78.2".to_string());
+                        map.insert("limit".to_string(), "200000".to_string());
+                        map.insert("limit_k".to_string(), "200k".to_string());
+                        map.insert("compact".to_string(), "78.2%".to_string());
                         map
                     },
                 },
                 SegmentId::Usage => SegmentData {
                     primary: "24%".to_string(),
                     secondary: "· 10-7-2".to_string(),
-                    metadata: HashMap::new(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("five_hour".to_string(), "24".to_string());
+                        map.insert("seven_day".to_string(), "12".to_string());
+                        map.insert("resets".to_string(), "10-7-2".to_string());
+                        map.insert("compact".to_string(), "24%".to_string());
+                        map
+                    },
                 },
                 SegmentId::Cost => SegmentData {
                     primary: "$0.02".to_string(),
@@ -147,6 +153,7 @@ impl PreviewComponent {
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("cost".to_string(), "0.01234".to_string());
+                        map.insert("cost_display".to_string(), "$0.02".to_string());
                         map
                     },
                 },
@@ -158,6 +165,9 @@ impl PreviewComponent {
                         map.insert("duration_ms".to_string(), "225000".to_string());
                         map.insert("lines_added".to_string(), "156".to_string());
                         map.insert("lines_removed".to_string(), "23".to_string());
+                        map.insert("duration".to_string(), "3m45s".to_string());
+                        map.insert("lines".to_string(), "+156 -23".to_string());
+                        map.insert("compact".to_string(), "3m45s".to_string());
                         map
                     },
                 },
@@ -180,23 +190,46 @@ impl PreviewComponent {
                             env!("CARGO_PKG_VERSION").to_string(),
                         );
                         map.insert("update_available".to_string(), "false".to_string());
+                        map.insert(
+                            "status".to_string(),
+                            format!("v{}", env!("CARGO_PKG_VERSION")),
+                        );
                         map
                     },
                 },
                 SegmentId::Used => SegmentData {
                     primary: "已用:$12.48".to_string(),
                     secondary: "".to_string(),
-                    metadata: HashMap::new(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("used".to_string(), "12.48".to_string());
+                        map.insert("used_display".to_string(), "$12.48".to_string());
+                        map.insert("compact".to_string(), "$12.48".to_string());
+                        map
+                    },
                 },
                 SegmentId::Balance => SegmentData {
                     primary: "余额:$137.52".to_string(),
                     secondary: "".to_string(),
-                    metadata: HashMap::new(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("balance".to_string(), "137.52".to_string());
+                        map.insert("balance_display".to_string(), "$137.52".to_string());
+                        map.insert("compact".to_string(), "$137.52".to_string());
+                        map.insert("is_unlimited".to_string(), "false".to_string());
+                        map
+                    },
                 },
                 SegmentId::Cwd => SegmentData {
                     primary: "~/work/eflow".to_string(),
                     secondary: "".to_string(),
-                    metadata: HashMap::new(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("full_path".to_string(), "/home/user/work/eflow".to_string());
+                        map.insert("path".to_string(), "~/work/eflow".to_string());
+                        map.insert("compact".to_string(), "eflow".to_string());
+                        map
+                    },
                 },
                 SegmentId::Branding => SegmentData {
                     primary: String::new(),
@@ -21,6 +21,8 @@ pub enum FieldSelection {
     TextColor,
     BackgroundColor,
     TextStyle,
+    Align,
+    Thresholds,
     Options,
 }
 
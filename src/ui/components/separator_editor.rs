@@ -53,6 +53,26 @@ impl SeparatorEditorComponent {
                 value: "\u{e0b0}".to_string(),
                 description: "Powerline arrow (seamless transition)".to_string(),
             },
+            SeparatorPreset {
+                name: "Thin Arrow".to_string(),
+                value: "\u{e0b1}".to_string(),
+                description: "Powerline outline arrow".to_string(),
+            },
+            SeparatorPreset {
+                name: "Rounded".to_string(),
+                value: "\u{e0b4}".to_string(),
+                description: "Rounded powerline edge".to_string(),
+            },
+            SeparatorPreset {
+                name: "Slanted".to_string(),
+                value: "\u{e0bc}".to_string(),
+                description: "Slanted powerline edge".to_string(),
+            },
+            SeparatorPreset {
+                name: "Flame".to_string(),
+                value: "\u{e0c0}".to_string(),
+                description: "Flame powerline edge".to_string(),
+            },
             SeparatorPreset {
                 name: "Space".to_string(),
                 value: "  ".to_string(),
@@ -125,7 +145,7 @@ impl SeparatorEditorComponent {
         }
 
         // Calculate exact size needed
-        let popup_height = 15;
+        let popup_height = 19;
         let popup_width = 60;
         let popup_area = Rect {
             x: (area.width.saturating_sub(popup_width)) / 2,
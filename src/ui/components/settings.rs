@@ -1,4 +1,6 @@
 use super::segment_list::{FieldSelection, Panel};
+use super::text_style_editor::TextStyleEditorComponent;
+use super::threshold_editor::ThresholdEditorComponent;
 use crate::config::{Config, SegmentId, StyleMode};
 use ratatui::{
     layout::Rect,
@@ -262,14 +264,25 @@ impl SettingsComponent {
                 create_field_line(
                     FieldSelection::TextStyle,
                     vec![Span::raw(format!(
-                        "├─ Text Style: Bold {}",
-                        if segment.styles.text_bold {
-                            "[✓]"
-                        } else {
-                            "[ ]"
-                        }
+                        "├─ Text Style: {}",
+                        TextStyleEditorComponent::describe(&segment.styles)
                     ))],
                 ),
+                create_field_line(
+                    FieldSelection::Align,
+                    vec![Span::raw(format!("├─ Align: {:?}", segment.align))],
+                ),
+                create_field_line(
+                    FieldSelection::Thresholds,
+                    vec![Span::raw(match segment.thresholds.as_slice() {
+                        [] => "├─ Thresholds: none".to_string(),
+                        [rule] => format!(
+                            "├─ Thresholds: {}",
+                            ThresholdEditorComponent::describe(segment.id, rule)
+                        ),
+                        rules => format!("├─ Thresholds: {} rules", rules.len()),
+                    })],
+                ),
                 create_field_line(
                     FieldSelection::Options,
                     vec![Span::raw(format!(
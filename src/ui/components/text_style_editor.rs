@@ -0,0 +1,258 @@
+use crate::config::{AnsiColor, SegmentConfig, TextStyleConfig, Underline};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Rows of the popup, top to bottom.
+const FIELDS: [Field; 7] = [
+    Field::Bold,
+    Field::Italic,
+    Field::Dim,
+    Field::Underline,
+    Field::UnderlineColor,
+    Field::Strikethrough,
+    Field::Inverse,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Bold,
+    Italic,
+    Dim,
+    Underline,
+    UnderlineColor,
+    Strikethrough,
+    Inverse,
+}
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Bold => "Bold",
+            Field::Italic => "Italic",
+            Field::Dim => "Dim",
+            Field::Underline => "Underline",
+            Field::UnderlineColor => "Underline Color",
+            Field::Strikethrough => "Strikethrough",
+            Field::Inverse => "Inverse",
+        }
+    }
+
+    /// Whether the attribute behind a checkbox row is on.
+    fn enabled(self, styles: &TextStyleConfig) -> Option<bool> {
+        match self {
+            Field::Bold => Some(styles.text_bold),
+            Field::Italic => Some(styles.text_italic),
+            Field::Dim => Some(styles.text_dim),
+            Field::Strikethrough => Some(styles.text_strikethrough),
+            Field::Inverse => Some(styles.text_inverse),
+            Field::Underline | Field::UnderlineColor => None,
+        }
+    }
+
+    /// The on/off attribute behind a checkbox row.
+    fn flag(self, styles: &mut TextStyleConfig) -> Option<&mut bool> {
+        match self {
+            Field::Bold => Some(&mut styles.text_bold),
+            Field::Italic => Some(&mut styles.text_italic),
+            Field::Dim => Some(&mut styles.text_dim),
+            Field::Strikethrough => Some(&mut styles.text_strikethrough),
+            Field::Inverse => Some(&mut styles.text_inverse),
+            Field::Underline | Field::UnderlineColor => None,
+        }
+    }
+}
+
+/// Popup editing the text attributes of a segment. Edits are applied directly
+/// to the segment config passed in by the app.
+#[derive(Debug, Clone, Default)]
+pub struct TextStyleEditorComponent {
+    pub is_open: bool,
+    pub selected: usize,
+    /// The color picker was opened for the underline color.
+    pub picking_color: bool,
+}
+
+impl TextStyleEditorComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.selected = 0;
+        self.picking_color = false;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.picking_color = false;
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        let last = FIELDS.len() as i32 - 1;
+        self.selected = (self.selected as i32 + delta).clamp(0, last) as usize;
+    }
+
+    /// Toggle the selected attribute or cycle the underline shape. Returns
+    /// `true` when the underline color row wants the color picker.
+    pub fn activate(&mut self, segment: &mut SegmentConfig) -> bool {
+        let styles = &mut segment.styles;
+        match FIELDS[self.selected] {
+            field @ (Field::Bold
+            | Field::Italic
+            | Field::Dim
+            | Field::Strikethrough
+            | Field::Inverse) => {
+                if let Some(flag) = field.flag(styles) {
+                    *flag = !*flag;
+                }
+            }
+            Field::Underline => {
+                styles.text_underline = match styles.text_underline {
+                    Underline::None => Underline::Single,
+                    Underline::Single => Underline::Double,
+                    Underline::Double => Underline::Curly,
+                    Underline::Curly => Underline::Dotted,
+                    Underline::Dotted => Underline::Dashed,
+                    Underline::Dashed => Underline::None,
+                }
+            }
+            Field::UnderlineColor => {
+                self.picking_color = true;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Go back to underlining in the text color.
+    pub fn clear_color(&mut self, segment: &mut SegmentConfig) {
+        if FIELDS[self.selected] == Field::UnderlineColor {
+            segment.styles.underline_color = None;
+        }
+    }
+
+    pub fn apply_color(&mut self, segment: &mut SegmentConfig, color: AnsiColor) {
+        if std::mem::take(&mut self.picking_color) {
+            segment.styles.underline_color = Some(color);
+        }
+    }
+
+    /// One-line summary used by the settings panel.
+    pub fn describe(styles: &TextStyleConfig) -> String {
+        let underline = match styles.text_underline {
+            Underline::None => None,
+            Underline::Single => Some("Underline"),
+            Underline::Double => Some("Double underline"),
+            Underline::Curly => Some("Curly underline"),
+            Underline::Dotted => Some("Dotted underline"),
+            Underline::Dashed => Some("Dashed underline"),
+        };
+        let names: Vec<&str> = [
+            (styles.text_bold, "Bold"),
+            (styles.text_italic, "Italic"),
+            (styles.text_dim, "Dim"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| name)
+        .chain(underline)
+        .chain(
+            [
+                (styles.text_strikethrough, "Strikethrough"),
+                (styles.text_inverse, "Inverse"),
+            ]
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, name)| name),
+        )
+        .collect();
+
+        if names.is_empty() {
+            "Normal".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+
+    fn value(field: Field, styles: &TextStyleConfig) -> String {
+        match field {
+            Field::Bold | Field::Italic | Field::Dim | Field::Strikethrough | Field::Inverse => {
+                let enabled = field.enabled(styles).unwrap_or_default();
+                if enabled { "[✓]" } else { "[ ]" }.to_string()
+            }
+            Field::Underline => format!("{:?}", styles.text_underline),
+            Field::UnderlineColor => match &styles.underline_color {
+                Some(AnsiColor::Color16 { c16 }) => format!("16:{}", c16),
+                Some(AnsiColor::Color256 { c256 }) => format!("256:{}", c256),
+                Some(AnsiColor::Rgb { r, g, b }) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+                None => "Text color".to_string(),
+            },
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, segment: &SegmentConfig) {
+        if !self.is_open {
+            return;
+        }
+
+        let popup_height = 15;
+        let popup_width = 48.min(area.width);
+        let popup_area = Rect {
+            x: (area.width.saturating_sub(popup_width)) / 2,
+            y: (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height.min(area.height),
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default().borders(Borders::ALL).title("Text Style");
+        let inner = popup_block.inner(popup_area);
+        f.render_widget(popup_block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(9), Constraint::Length(4)])
+            .split(inner);
+
+        let lines: Vec<Line> = FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let text = format!(
+                    "{:<16} {}",
+                    field.label(),
+                    Self::value(*field, &segment.styles)
+                );
+                if i == self.selected {
+                    Line::from(Span::styled(
+                        format!("▶ {}", text),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                } else {
+                    Line::from(format!("  {}", text))
+                }
+            })
+            .collect();
+
+        f.render_widget(
+            Paragraph::new(Text::from(lines))
+                .block(Block::default().borders(Borders::ALL).title("Attributes")),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new("[↑↓] Select  [Enter] Toggle/Cycle\n[Del] Reset Color  [Esc] Close")
+                .block(Block::default().borders(Borders::ALL)),
+            chunks[1],
+        );
+    }
+}
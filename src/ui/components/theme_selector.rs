@@ -1,12 +1,16 @@
 use crate::config::Config;
+use crate::core::width::visible_width;
+use crate::ui::themes::ThemePresets;
 use ratatui::{
     layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
-/// Theme is locked to a single hard-coded preset; this component just shows
-/// the current name plus the active separator for context.
+/// Shows the available themes (built-in presets and theme files) with the
+/// active one highlighted, plus the current separator.
 #[derive(Default)]
 pub struct ThemeSelectorComponent;
 
@@ -15,14 +19,93 @@ impl ThemeSelectorComponent {
         Self
     }
 
+    /// Height needed to show every theme name at the given width, including borders.
+    pub fn required_height(&self, total_width: u16) -> u16 {
+        let content_width = total_width.saturating_sub(2) as usize;
+        let lines = Self::wrap_names(&ThemePresets::list_themes(), content_width).len() as u16;
+        // theme lines + separator line + borders
+        (lines + 1 + 2).clamp(4, 8)
+    }
+
     pub fn render(&self, f: &mut Frame, area: Rect, config: &Config) {
-        let body = format!(
-            "Theme: {} (locked)\nSeparator: \"{}\"",
-            config.theme, config.style.separator
-        );
-        let widget = Paragraph::new(body)
-            .block(Block::default().borders(Borders::ALL).title("Theme"))
-            .wrap(ratatui::widgets::Wrap { trim: false });
+        let themes = ThemePresets::list_themes();
+        let content_width = area.width.saturating_sub(2) as usize;
+        let modified = config.is_modified_from_theme();
+
+        let mut lines: Vec<Line> = Self::wrap_names(&themes, content_width)
+            .into_iter()
+            .map(|row| {
+                let mut spans = Vec::new();
+                for (i, (index, name)) in row.into_iter().enumerate() {
+                    if i > 0 {
+                        spans.push(Span::raw("  "));
+                    }
+                    let label = Self::label(index, &name);
+                    if name == config.theme {
+                        let marker = if modified { "*" } else { "" };
+                        spans.push(Span::styled(
+                            format!("▶ {}{}", label, marker),
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                    } else {
+                        spans.push(Span::raw(format!("  {}", label)));
+                    }
+                }
+                Line::from(spans)
+            })
+            .collect();
+
+        lines.push(Line::from(Span::styled(
+            format!("Separator: \"{}\"", config.style.separator),
+            Style::default().fg(Color::Gray),
+        )));
+
+        let title = if modified {
+            "Theme (modified)"
+        } else {
+            "Theme"
+        };
+        let widget = Paragraph::new(Text::from(lines))
+            .block(Block::default().borders(Borders::ALL).title(title));
         f.render_widget(widget, area);
     }
+
+    /// Built-in themes get their `[1-4]` shortcut in front of the name.
+    fn label(index: usize, name: &str) -> String {
+        if index < crate::ui::themes::BUILTIN_THEMES.len() {
+            format!("[{}] {}", index + 1, name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Greedily pack theme labels into rows no wider than `width`.
+    fn wrap_names(themes: &[String], width: usize) -> Vec<Vec<(usize, String)>> {
+        let mut rows: Vec<Vec<(usize, String)>> = vec![Vec::new()];
+        let mut current = 0usize;
+
+        for (index, name) in themes.iter().enumerate() {
+            // "▶ " / "  " prefix + label + optional "*" marker
+            let item_width = visible_width(&Self::label(index, name)) + 3;
+            let needed = if current == 0 {
+                item_width
+            } else {
+                item_width + 2
+            };
+
+            if current > 0 && current + needed > width {
+                rows.push(Vec::new());
+                current = item_width;
+            } else {
+                current += needed;
+            }
+            if let Some(row) = rows.last_mut() {
+                row.push((index, name.clone()));
+            }
+        }
+
+        rows
+    }
 }
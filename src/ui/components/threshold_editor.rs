@@ -0,0 +1,230 @@
+use crate::config::{AnsiColor, ColorConfig, SegmentConfig, SegmentId, ThresholdRule};
+use crate::core::thresholds::default_metric;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Which color of the selected rule the color picker edits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdColorTarget {
+    Text,
+    Background,
+}
+
+/// Popup listing a segment's threshold rules. Edits are applied directly to
+/// the segment config passed in by the app.
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdEditorComponent {
+    pub is_open: bool,
+    pub selected: usize,
+    pub color_target: Option<ThresholdColorTarget>,
+}
+
+impl ThresholdEditorComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.selected = 0;
+        self.color_target = None;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.color_target = None;
+    }
+
+    pub fn move_selection(&mut self, delta: i32, segment: &SegmentConfig) {
+        let last = segment.thresholds.len().saturating_sub(1) as i32;
+        self.selected = (self.selected as i32 + delta).clamp(0, last) as usize;
+    }
+
+    /// Append a starter rule suited to the segment's metric.
+    pub fn add_rule(&mut self, segment: &mut SegmentConfig) -> bool {
+        if default_metric(segment.id).is_none() {
+            return false;
+        }
+        let (above, below) = match segment.id {
+            SegmentId::Balance => (None, Some(5.0)),
+            SegmentId::Cost => (Some(5.0), None),
+            SegmentId::Used => (Some(100.0), None),
+            _ => (Some(80.0), None),
+        };
+        segment.thresholds.push(ThresholdRule {
+            key: None,
+            above,
+            below,
+            colors: ColorConfig {
+                icon: None,
+                text: None,
+                background: Some(AnsiColor::Color16 { c16: 1 }),
+            },
+        });
+        self.selected = segment.thresholds.len() - 1;
+        true
+    }
+
+    pub fn delete_rule(&mut self, segment: &mut SegmentConfig) {
+        if self.selected < segment.thresholds.len() {
+            segment.thresholds.remove(self.selected);
+            self.selected = self
+                .selected
+                .min(segment.thresholds.len().saturating_sub(1));
+        }
+    }
+
+    /// Nudge the selected rule's bound up or down.
+    pub fn adjust_value(&mut self, segment: &mut SegmentConfig, direction: f64) {
+        let step = match segment.id {
+            SegmentId::ContextWindow | SegmentId::Usage => 5.0,
+            _ => 1.0,
+        };
+        if let Some(rule) = segment.thresholds.get_mut(self.selected) {
+            if let Some(bound) = rule.above.as_mut().or(rule.below.as_mut()) {
+                *bound = (*bound + step * direction).max(0.0);
+            }
+        }
+    }
+
+    /// Switch the selected rule between `above` (>=) and `below` (<).
+    pub fn toggle_comparison(&mut self, segment: &mut SegmentConfig) {
+        if let Some(rule) = segment.thresholds.get_mut(self.selected) {
+            match (rule.above, rule.below) {
+                (Some(value), None) => {
+                    rule.above = None;
+                    rule.below = Some(value);
+                }
+                (None, Some(value)) => {
+                    rule.below = None;
+                    rule.above = Some(value);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn apply_color(&mut self, segment: &mut SegmentConfig, color: AnsiColor) {
+        if let (Some(target), Some(rule)) = (
+            self.color_target.take(),
+            segment.thresholds.get_mut(self.selected),
+        ) {
+            match target {
+                ThresholdColorTarget::Text => rule.colors.text = Some(color),
+                ThresholdColorTarget::Background => rule.colors.background = Some(color),
+            }
+        }
+    }
+
+    /// One-line summary used by the settings panel and the rule list.
+    pub fn describe(id: SegmentId, rule: &ThresholdRule) -> String {
+        let key = rule
+            .key
+            .as_deref()
+            .or_else(|| default_metric(id))
+            .unwrap_or("?");
+        let condition = match (rule.above, rule.below) {
+            (Some(above), Some(below)) => format!("{} ≤ {} < {}", above, key, below),
+            (Some(above), None) => format!("{} ≥ {}", key, above),
+            (None, Some(below)) => format!("{} < {}", key, below),
+            (None, None) => format!("{} (no bound)", key),
+        };
+        let mut colors = Vec::new();
+        if let Some(color) = &rule.colors.text {
+            colors.push(format!("text {}", Self::color_name(color)));
+        }
+        if let Some(color) = &rule.colors.background {
+            colors.push(format!("bg {}", Self::color_name(color)));
+        }
+        format!("{} → {}", condition, colors.join(", "))
+    }
+
+    fn color_name(color: &AnsiColor) -> String {
+        match color {
+            AnsiColor::Color16 { c16 } => format!("16:{}", c16),
+            AnsiColor::Color256 { c256 } => format!("256:{}", c256),
+            AnsiColor::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, segment: &SegmentConfig) {
+        if !self.is_open {
+            return;
+        }
+
+        let popup_height = 14;
+        let popup_width = 64.min(area.width);
+        let popup_area = Rect {
+            x: (area.width.saturating_sub(popup_width)) / 2,
+            y: (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height.min(area.height),
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .borders(Borders::ALL)
+            .title("Threshold Colors");
+        let inner = popup_block.inner(popup_area);
+        f.render_widget(popup_block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(4)])
+            .split(inner);
+
+        let lines: Vec<Line> = if segment.thresholds.is_empty() {
+            let hint = if default_metric(segment.id).is_some() {
+                "No rules yet, press [A] to add one"
+            } else {
+                "This segment has no numeric metric"
+            };
+            vec![Line::from(Span::styled(
+                hint,
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            segment
+                .thresholds
+                .iter()
+                .enumerate()
+                .map(|(i, rule)| {
+                    let text = Self::describe(segment.id, rule);
+                    if i == self.selected {
+                        Line::from(Span::styled(
+                            format!("▶ {}", text),
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        ))
+                    } else {
+                        Line::from(format!("  {}", text))
+                    }
+                })
+                .collect()
+        };
+
+        f.render_widget(
+            Paragraph::new(Text::from(lines)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Rules (later rules win)"),
+            ),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new(
+                "[A] Add  [D] Delete  [←→] Value  [Tab] ≥/<\n[B] Background  [T] Text Color  [Esc] Close",
+            )
+            .block(Block::default().borders(Borders::ALL)),
+            chunks[1],
+        );
+    }
+}
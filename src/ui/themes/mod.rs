@@ -1,4 +1,7 @@
 pub mod presets;
 pub mod theme_default;
+pub mod theme_gruvbox;
+pub mod theme_minimal;
+pub mod theme_nord;
 
 pub use presets::*;
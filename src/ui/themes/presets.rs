@@ -1,19 +1,167 @@
-// The theme is now a single hard-coded preset (the powerline-arrow style designed
-// for new-api / EFlowCode). Theme switching, file-based themes, and the TUI theme
-// selector are all gone — `ThemePresets::get_default()` is the only path.
+// Theme registry: built-in presets plus user themes loaded from
+// `~/.claude/eflowcodeline/themes/*.toml`. A theme file is a complete `Config`;
+// a file whose name matches a built-in preset shadows that preset.
 
-use crate::config::{Config, StyleConfig, StyleMode};
+use crate::config::{ColorDepth, Config, SegmentId, StyleConfig, StyleMode};
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use super::theme_default;
+use super::{theme_default, theme_gruvbox, theme_minimal, theme_nord};
+
+/// Built-in theme names, in the order the TUI cycles through them.
+pub const BUILTIN_THEMES: &[&str] = &["default", "minimal", "gruvbox", "nord"];
+
+/// Row layout shared by the built-in themes: upstream metrics, then
+/// path/git, then account balance and branding.
+pub fn default_rows() -> Vec<Vec<SegmentId>> {
+    vec![
+        vec![
+            SegmentId::Model,
+            SegmentId::ContextWindow,
+            SegmentId::Usage,
+            SegmentId::Cost,
+            SegmentId::Session,
+            SegmentId::OutputStyle,
+            SegmentId::Update,
+        ],
+        vec![SegmentId::Cwd, SegmentId::Directory, SegmentId::Git],
+        vec![SegmentId::Used, SegmentId::Balance, SegmentId::Branding],
+    ]
+}
+
+/// Theme names double as file names, so they are restricted to plain words.
+pub fn is_valid_theme_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn theme_file(dir: &Path, theme_name: &str) -> Option<PathBuf> {
+    is_valid_theme_name(theme_name).then(|| dir.join(format!("{}.toml", theme_name)))
+}
 
 pub struct ThemePresets;
 
 impl ThemePresets {
-    /// Returns the only configured theme.
-    /// Kept as `get_theme(&str)` for compatibility with code paths that still
-    /// pass a name through; the argument is ignored on purpose.
-    pub fn get_theme(_theme_name: &str) -> Config {
-        Self::get_default()
+    /// Resolve a theme by name: user theme file first, then built-in presets,
+    /// falling back to the default theme for unknown names.
+    pub fn get_theme(theme_name: &str) -> Config {
+        Self::resolve(theme_name).0
+    }
+
+    /// Like `get_theme`, also explaining why the named theme was not used:
+    /// a theme file that does not parse, or a name no theme answers to.
+    pub fn resolve(theme_name: &str) -> (Config, Option<String>) {
+        Self::resolve_in(Self::themes_dir().as_deref(), theme_name)
+    }
+
+    fn resolve_in(dir: Option<&Path>, theme_name: &str) -> (Config, Option<String>) {
+        let file_error = match dir.and_then(|dir| theme_file(dir, theme_name)) {
+            Some(path) => match Self::load_theme_file(&path, theme_name) {
+                Ok(Some(config)) => return (config, None),
+                Ok(None) => None,
+                Err(e) => Some(e),
+            },
+            None => None,
+        };
+
+        match Self::get_builtin(theme_name) {
+            Some(config) => (config, file_error),
+            None => {
+                let error =
+                    file_error.unwrap_or_else(|| "unknown theme, using \"default\"".to_string());
+                (Self::get_default(), Some(error))
+            }
+        }
+    }
+
+    /// Built-in preset by name, ignoring theme files.
+    pub fn get_builtin(theme_name: &str) -> Option<Config> {
+        match theme_name {
+            "default" => Some(Self::get_default()),
+            "minimal" => Some(Self::get_minimal()),
+            "gruvbox" => Some(Self::get_gruvbox()),
+            "nord" => Some(Self::get_nord()),
+            _ => None,
+        }
+    }
+
+    /// All available theme names: built-ins first, then user theme files.
+    pub fn list_themes() -> Vec<String> {
+        Self::list_themes_in(Self::themes_dir().as_deref())
+    }
+
+    fn list_themes_in(dir: Option<&Path>) -> Vec<String> {
+        let mut themes: Vec<String> = BUILTIN_THEMES.iter().map(|s| s.to_string()).collect();
+
+        let mut custom: Vec<String> = dir
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("toml"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .filter(|name| is_valid_theme_name(name) && !themes.contains(name))
+            .collect();
+        custom.sort();
+
+        themes.extend(custom);
+        themes
+    }
+
+    /// Directory holding user theme files: `~/.claude/eflowcodeline/themes`
+    pub fn themes_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".claude").join("eflowcodeline").join("themes"))
+    }
+
+    /// Path of the theme file for `theme_name`, whether or not it exists.
+    /// `None` for names that are not plain `[A-Za-z0-9_-]` words, so a theme
+    /// name can never point outside the themes directory.
+    pub fn theme_path(theme_name: &str) -> Option<PathBuf> {
+        theme_file(&Self::themes_dir()?, theme_name)
+    }
+
+    /// `Ok(None)` when there is no such file.
+    fn load_theme_file(path: &Path, theme_name: &str) -> Result<Option<Config>, String> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(None);
+        };
+        let mut config: Config = toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        config.theme = theme_name.to_string();
+        Ok(Some(config))
+    }
+
+    /// Write `config` as the theme file for `theme_name`.
+    pub fn save_theme(
+        theme_name: &str,
+        config: &Config,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dir = Self::themes_dir().ok_or("Could not determine home directory")?;
+        Self::save_theme_in(&dir, theme_name, config)
+    }
+
+    fn save_theme_in(
+        dir: &Path,
+        theme_name: &str,
+        config: &Config,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let path = theme_file(dir, theme_name).ok_or_else(|| {
+            format!(
+                "Invalid theme name {:?}: use letters, digits, '-' and '_'",
+                theme_name
+            )
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut theme = config.clone();
+        theme.theme = theme_name.to_string();
+        fs::write(&path, toml::to_string_pretty(&theme)?)?;
+        Ok(path)
     }
 
     pub fn get_default() -> Config {
@@ -21,7 +169,13 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "\u{e0b0}".to_string(),
+                start_cap: String::new(),
+                end_cap: String::new(),
+                color_depth: ColorDepth::Auto,
+                hyperlinks: true,
+                gradient: Vec::new(),
             },
+            rows: default_rows(),
             segments: vec![
                 theme_default::model_segment(),
                 theme_default::context_window_segment(),
@@ -29,16 +183,175 @@ impl ThemePresets {
                 theme_default::cost_segment(),
                 theme_default::session_segment(),
                 theme_default::output_style_segment(),
-                // -- newline injected before Cwd by the renderer --
                 theme_default::cwd_segment(),
                 theme_default::directory_segment(),
                 theme_default::git_segment(),
                 theme_default::used_segment(),
                 theme_default::balance_segment(),
-                // -- newline injected before Branding by the renderer --
                 theme_default::branding_segment(),
             ],
             theme: "default".to_string(),
         }
     }
+
+    pub fn get_minimal() -> Config {
+        Config {
+            style: StyleConfig {
+                mode: StyleMode::Plain,
+                separator: " │ ".to_string(),
+                start_cap: String::new(),
+                end_cap: String::new(),
+                color_depth: ColorDepth::Auto,
+                hyperlinks: true,
+                gradient: Vec::new(),
+            },
+            rows: default_rows(),
+            segments: vec![
+                theme_minimal::model_segment(),
+                theme_minimal::context_window_segment(),
+                theme_minimal::usage_segment(),
+                theme_minimal::cost_segment(),
+                theme_minimal::session_segment(),
+                theme_minimal::output_style_segment(),
+                theme_minimal::cwd_segment(),
+                theme_minimal::directory_segment(),
+                theme_minimal::git_segment(),
+                theme_minimal::used_segment(),
+                theme_minimal::balance_segment(),
+                theme_minimal::branding_segment(),
+            ],
+            theme: "minimal".to_string(),
+        }
+    }
+
+    pub fn get_gruvbox() -> Config {
+        Config {
+            style: StyleConfig {
+                mode: StyleMode::NerdFont,
+                separator: " | ".to_string(),
+                start_cap: String::new(),
+                end_cap: String::new(),
+                color_depth: ColorDepth::Auto,
+                hyperlinks: true,
+                gradient: Vec::new(),
+            },
+            rows: default_rows(),
+            segments: vec![
+                theme_gruvbox::model_segment(),
+                theme_gruvbox::context_window_segment(),
+                theme_gruvbox::usage_segment(),
+                theme_gruvbox::cost_segment(),
+                theme_gruvbox::session_segment(),
+                theme_gruvbox::output_style_segment(),
+                theme_gruvbox::cwd_segment(),
+                theme_gruvbox::directory_segment(),
+                theme_gruvbox::git_segment(),
+                theme_gruvbox::used_segment(),
+                theme_gruvbox::balance_segment(),
+                theme_gruvbox::branding_segment(),
+            ],
+            theme: "gruvbox".to_string(),
+        }
+    }
+
+    pub fn get_nord() -> Config {
+        Config {
+            style: StyleConfig {
+                mode: StyleMode::NerdFont,
+                separator: "\u{e0b0}".to_string(),
+                start_cap: String::new(),
+                end_cap: String::new(),
+                color_depth: ColorDepth::Auto,
+                hyperlinks: true,
+                gradient: Vec::new(),
+            },
+            rows: default_rows(),
+            segments: vec![
+                theme_nord::model_segment(),
+                theme_nord::context_window_segment(),
+                theme_nord::usage_segment(),
+                theme_nord::cost_segment(),
+                theme_nord::session_segment(),
+                theme_nord::output_style_segment(),
+                theme_nord::cwd_segment(),
+                theme_nord::directory_segment(),
+                theme_nord::git_segment(),
+                theme_nord::used_segment(),
+                theme_nord::balance_segment(),
+                theme_nord::branding_segment(),
+            ],
+            theme: "nord".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AnsiColor;
+
+    #[test]
+    fn theme_names_stay_inside_the_themes_directory() {
+        let dir = Path::new("/themes");
+        assert_eq!(
+            theme_file(dir, "my-theme_2"),
+            Some(PathBuf::from("/themes/my-theme_2.toml"))
+        );
+        for name in ["", "../../etc/passwd", "/abs/path", "a.b", "a b"] {
+            assert_eq!(theme_file(dir, name), None, "{:?}", name);
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(ThemePresets::save_theme_in(tmp.path(), "../escape", &Config::default()).is_err());
+    }
+
+    #[test]
+    fn theme_files_shadow_built_ins_and_list_after_them() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Some(tmp.path());
+
+        let mut nord = ThemePresets::get_nord();
+        nord.style.separator = "|".to_string();
+        ThemePresets::save_theme_in(tmp.path(), "nord", &nord).unwrap();
+        let mut mine = ThemePresets::get_minimal();
+        mine.segments[0].colors.icon = Some(AnsiColor::Color16 { c16: 3 });
+        ThemePresets::save_theme_in(tmp.path(), "mine", &mine).unwrap();
+        fs::write(tmp.path().join("bad name.toml"), "").unwrap();
+
+        assert_eq!(
+            ThemePresets::list_themes_in(dir),
+            ["default", "minimal", "gruvbox", "nord", "mine"]
+        );
+
+        let (config, error) = ThemePresets::resolve_in(dir, "nord");
+        assert_eq!((config.style.separator.as_str(), error), ("|", None));
+        let (config, error) = ThemePresets::resolve_in(dir, "mine");
+        assert_eq!(config.theme, "mine");
+        assert_eq!(
+            config.segments[0].colors.icon,
+            Some(AnsiColor::Color16 { c16: 3 })
+        );
+        assert_eq!(error, None);
+    }
+
+    #[test]
+    fn broken_and_unknown_themes_fall_back_with_a_reason() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Some(tmp.path());
+        fs::write(tmp.path().join("gruvbox.toml"), "style = [").unwrap();
+        fs::write(tmp.path().join("broken.toml"), "style = [").unwrap();
+
+        // A broken file shadowing a preset falls back to that preset
+        let (config, error) = ThemePresets::resolve_in(dir, "gruvbox");
+        assert_eq!(config.theme, "gruvbox");
+        assert!(error.unwrap().contains("gruvbox.toml"));
+
+        let (config, error) = ThemePresets::resolve_in(dir, "broken");
+        assert_eq!(config.theme, "default");
+        assert!(error.unwrap().contains("broken.toml"));
+
+        let (config, error) = ThemePresets::resolve_in(dir, "nope");
+        assert_eq!(config.theme, "default");
+        assert!(error.unwrap().contains("unknown theme"));
+        assert_eq!(ThemePresets::resolve_in(None, "minimal").1, None);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::config::{
-    AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
+    Align, AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
 };
 use std::collections::HashMap;
 
@@ -30,6 +30,10 @@ pub fn model_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -60,6 +64,10 @@ pub fn context_window_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -90,6 +98,10 @@ pub fn usage_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -120,6 +132,10 @@ pub fn cost_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -150,6 +166,10 @@ pub fn session_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -180,6 +200,10 @@ pub fn output_style_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -210,6 +234,10 @@ pub fn cwd_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -240,6 +268,10 @@ pub fn directory_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -270,6 +302,10 @@ pub fn git_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -300,6 +336,10 @@ pub fn used_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -330,6 +370,10 @@ pub fn balance_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
 
@@ -360,5 +404,9 @@ pub fn branding_segment() -> SegmentConfig {
         },
         styles: TextStyleConfig::default(),
         options: HashMap::new(),
+        thresholds: Vec::new(),
+        align: Align::Left,
+        priority: None,
+        min_width: None,
     }
 }
@@ -0,0 +1,97 @@
+use super::theme_default;
+use crate::config::{AnsiColor, ColorConfig, SegmentConfig};
+
+// Gruvbox keeps the default icons and layout, colored with the gruvbox
+// 256-color foreground palette and no segment backgrounds.
+
+fn colors(c256: u8) -> ColorConfig {
+    ColorConfig {
+        icon: Some(AnsiColor::Color256 { c256 }),
+        text: Some(AnsiColor::Color256 { c256 }),
+        background: None,
+    }
+}
+
+pub fn model_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(208), // orange
+        ..theme_default::model_segment()
+    }
+}
+
+pub fn context_window_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(214), // yellow
+        ..theme_default::context_window_segment()
+    }
+}
+
+pub fn usage_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(142), // green
+        ..theme_default::usage_segment()
+    }
+}
+
+pub fn cost_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(175), // purple
+        ..theme_default::cost_segment()
+    }
+}
+
+pub fn session_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(109), // blue
+        ..theme_default::session_segment()
+    }
+}
+
+pub fn output_style_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(108), // aqua
+        ..theme_default::output_style_segment()
+    }
+}
+
+pub fn cwd_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(223), // fg
+        ..theme_default::cwd_segment()
+    }
+}
+
+pub fn directory_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(214),
+        ..theme_default::directory_segment()
+    }
+}
+
+pub fn git_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(142),
+        ..theme_default::git_segment()
+    }
+}
+
+pub fn used_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(167), // red
+        ..theme_default::used_segment()
+    }
+}
+
+pub fn balance_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(142),
+        ..theme_default::balance_segment()
+    }
+}
+
+pub fn branding_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(208),
+        ..theme_default::branding_segment()
+    }
+}
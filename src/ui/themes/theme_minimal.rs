@@ -0,0 +1,115 @@
+use super::theme_default;
+use crate::config::{AnsiColor, ColorConfig, IconConfig, SegmentConfig};
+
+// Minimal reuses the default segment set and options but drops the powerline
+// backgrounds in favour of plain 16-color foregrounds and simple glyphs.
+
+fn colors(c16: u8) -> ColorConfig {
+    ColorConfig {
+        icon: Some(AnsiColor::Color16 { c16 }),
+        text: Some(AnsiColor::Color16 { c16 }),
+        background: None,
+    }
+}
+
+fn icon(plain: &str, nerd_font: &str) -> IconConfig {
+    IconConfig {
+        plain: plain.to_string(),
+        nerd_font: nerd_font.to_string(),
+    }
+}
+
+pub fn model_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("✽", "\u{e26d}"),
+        colors: colors(7),
+        ..theme_default::model_segment()
+    }
+}
+
+pub fn context_window_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("◐", "\u{f49b}"),
+        colors: colors(7),
+        ..theme_default::context_window_segment()
+    }
+}
+
+pub fn usage_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("◔", "\u{f0a9e}"),
+        colors: colors(8),
+        ..theme_default::usage_segment()
+    }
+}
+
+pub fn cost_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("$", "\u{eec1}"),
+        colors: colors(8),
+        ..theme_default::cost_segment()
+    }
+}
+
+pub fn session_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("◷", "\u{f19bb}"),
+        colors: colors(8),
+        ..theme_default::session_segment()
+    }
+}
+
+pub fn output_style_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("◇", "\u{f12f5}"),
+        colors: colors(8),
+        ..theme_default::output_style_segment()
+    }
+}
+
+pub fn cwd_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("▸", "\u{f024b}"),
+        colors: colors(7),
+        ..theme_default::cwd_segment()
+    }
+}
+
+pub fn directory_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("▸", "\u{f024b}"),
+        colors: colors(7),
+        ..theme_default::directory_segment()
+    }
+}
+
+pub fn git_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("±", "\u{f02a2}"),
+        colors: colors(7),
+        ..theme_default::git_segment()
+    }
+}
+
+pub fn used_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("−", "\u{f155}"),
+        colors: colors(8),
+        ..theme_default::used_segment()
+    }
+}
+
+pub fn balance_segment() -> SegmentConfig {
+    SegmentConfig {
+        icon: icon("=", "\u{f0d6}"),
+        colors: colors(8),
+        ..theme_default::balance_segment()
+    }
+}
+
+pub fn branding_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(8),
+        ..theme_default::branding_segment()
+    }
+}
@@ -0,0 +1,109 @@
+use super::theme_default;
+use crate::config::{AnsiColor, ColorConfig, SegmentConfig};
+
+// Nord keeps the powerline layout of the default theme but swaps the neon
+// gradient for the Nord frost/aurora palette.
+
+fn colors(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> ColorConfig {
+    let fg = AnsiColor::Rgb {
+        r: fg.0,
+        g: fg.1,
+        b: fg.2,
+    };
+    ColorConfig {
+        icon: Some(fg.clone()),
+        text: Some(fg),
+        background: Some(AnsiColor::Rgb {
+            r: bg.0,
+            g: bg.1,
+            b: bg.2,
+        }),
+    }
+}
+
+const SNOW: (u8, u8, u8) = (236, 239, 244);
+const POLAR: (u8, u8, u8) = (46, 52, 64);
+
+pub fn model_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(SNOW, (76, 86, 106)),
+        ..theme_default::model_segment()
+    }
+}
+
+pub fn context_window_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(SNOW, (94, 129, 172)),
+        ..theme_default::context_window_segment()
+    }
+}
+
+pub fn usage_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (129, 161, 193)),
+        ..theme_default::usage_segment()
+    }
+}
+
+pub fn cost_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (136, 192, 208)),
+        ..theme_default::cost_segment()
+    }
+}
+
+pub fn session_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (143, 188, 187)),
+        ..theme_default::session_segment()
+    }
+}
+
+pub fn output_style_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (163, 190, 140)),
+        ..theme_default::output_style_segment()
+    }
+}
+
+pub fn cwd_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(SNOW, (59, 66, 82)),
+        ..theme_default::cwd_segment()
+    }
+}
+
+pub fn directory_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(SNOW, (67, 76, 94)),
+        ..theme_default::directory_segment()
+    }
+}
+
+pub fn git_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (163, 190, 140)),
+        ..theme_default::git_segment()
+    }
+}
+
+pub fn used_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (180, 142, 173)),
+        ..theme_default::used_segment()
+    }
+}
+
+pub fn balance_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (235, 203, 139)),
+        ..theme_default::balance_segment()
+    }
+}
+
+pub fn branding_segment() -> SegmentConfig {
+    SegmentConfig {
+        colors: colors(POLAR, (208, 135, 112)),
+        ..theme_default::branding_segment()
+    }
+}